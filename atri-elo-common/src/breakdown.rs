@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{EloMmr, PerformanceModel, Player, PlayerId, COEFF};
+
+/// The terms that make up a participant's new performance and rating, returned by [`EloMmr::apply_with_breakdown`].
+///
+/// Both equations are solved where their terms sum to zero, so a positive term pulls the solution down
+/// and a negative term pushes it up.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Breakdown<Id = u64> {
+    /// The performance of the participant.
+    pub perf: f64,
+    /// The new rating of the participant.
+    pub rating: f64,
+    /// The term of each participant in the performance equation, in `(player_id, term)` order.
+    ///
+    /// Those ranked above contribute `(tanh + 1) / δ`, those ranked below `(tanh - 1) / δ` and tied ones both.
+    /// The participant's own term is included. In team contests, the participants are teams identified by their
+    /// first member, and the terms are those of the team's performance.
+    pub opponents: Vec<(Id, f64)>,
+    /// The pull of the prior term on the new rating.
+    pub prior: f64,
+    /// The pull of each performance in the history on the new rating, the last being this contest's.
+    pub history: Vec<f64>,
+}
+
+impl<Id: PlayerId> Breakdown<Id> {
+    /// Collect the terms of a participant ranked in `lo..=hi` who performed `perf` and was updated to `player`.
+    ///
+    /// `ids` and `player_data` hold every participant's id and `(delta, mu_pi)` in rank order.
+    pub(crate) fn new(
+        system: &EloMmr<Id>,
+        player: &Player,
+        perf: f64,
+        (ids, player_data): (&[Id], &[(f64, f64)]),
+        (lo, hi): (u64, u64),
+    ) -> Breakdown<Id> {
+        let opponents = ids
+            .iter()
+            .zip(player_data)
+            .enumerate()
+            .map(|(j, (id, &(delta, mu_pi)))| {
+                let t = (COEFF * (perf - mu_pi) / (2.0 * delta)).tanh();
+                let mut term = 0.0;
+                if j + 1 >= lo as usize {
+                    term += delta.recip() * (t - 1.0);
+                }
+                if j < hi as usize {
+                    term += delta.recip() * (t + 1.0);
+                }
+                (id.clone(), term)
+            })
+            .collect();
+
+        let (beta, mu) = (system.beta, player.mu);
+        let history = player
+            .perfs
+            .iter()
+            .zip(&player.weights)
+            .skip(1)
+            .map(|(&p, &w)| match system.model {
+                PerformanceModel::Logistic => {
+                    COEFF * beta * w * (COEFF * (mu - p) / (2.0 * beta)).tanh()
+                }
+                PerformanceModel::Gaussian => w * (mu - p),
+            })
+            .collect();
+
+        Breakdown {
+            perf,
+            rating: mu,
+            opponents,
+            prior: player.weights[0] * (mu - player.perfs[0]),
+            history,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use dashmap::DashMap;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{standings, EloError, RatingSystem};
+
+/// An implementation of Codeforces rating algorithm.
+///
+/// Tied players are all given the worst rank of their tie, as Codeforces does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Codeforces {
+    rating_init: f64,
+
+    players: DashMap<u64, f64>,
+}
+
+impl Default for Codeforces {
+    fn default() -> Self {
+        Self::new(1500.0)
+    }
+}
+
+impl Codeforces {
+    /// Construct a new system.
+    ///
+    /// Default::default() gives a preset of superparameters (r_init = 1500).
+    pub fn new(rating_init: f64) -> Codeforces {
+        Codeforces {
+            rating_init,
+            players: DashMap::new(),
+        }
+    }
+}
+
+/// The probability that a player with rating `a` beats a player with rating `b`.
+fn win_probability(a: f64, b: f64) -> f64 {
+    (1.0 + 10f64.powf((b - a) / 400.0)).recip()
+}
+
+impl RatingSystem for Codeforces {
+    type Error = EloError;
+
+    /// Update ratings according to the explicit standings of the provided contest.
+    ///
+    /// The contest time is ignored.
+    ///
+    /// The returned performance is the rating needed to reach the geometric mean of the seed and the actual rank.
+    fn update_standings(
+        &mut self,
+        mut standings: Vec<(u64, u64, u64)>,
+        _time: u64,
+    ) -> Result<Vec<(u64, f64, f64)>, EloError> {
+        const ITERATIONS: usize = 100;
+        const SEARCH_MARGIN: f64 = 6000.0;
+
+        if standings.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        standings::check(&mut standings)?;
+        let ratings: Vec<f64> = standings
+            .iter()
+            .map(|(id, _, _)| *self.players.get(id).as_deref().unwrap_or(&self.rating_init))
+            .collect();
+
+        let seed = |rating: f64, skip: usize| {
+            1.0 + ratings
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != skip)
+                .map(|(_, &other)| win_probability(other, rating))
+                .sum::<f64>()
+        };
+
+        let min_rating = ratings.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_rating = ratings.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let mut perfs = Vec::with_capacity(standings.len());
+        standings
+            .par_iter()
+            .enumerate()
+            .map(|(i, &(_, _, hi))| {
+                let mean_rank = (seed(ratings[i], i) * hi as f64).sqrt();
+                let (mut a, mut b) = (min_rating - SEARCH_MARGIN, max_rating + SEARCH_MARGIN);
+                for _ in 0..ITERATIONS {
+                    let mid = 0.5 * (a + b);
+                    if seed(mid, i) < mean_rank {
+                        b = mid;
+                    } else {
+                        a = mid;
+                    }
+                }
+                0.5 * (a + b)
+            })
+            .collect_into_vec(&mut perfs);
+
+        let n = standings.len();
+        let mut deltas: Vec<f64> = perfs
+            .iter()
+            .zip(ratings.iter())
+            .map(|(perf, rating)| 0.5 * (perf - rating))
+            .collect();
+
+        // Keep the sum of rating changes slightly negative.
+        let inc = -deltas.iter().sum::<f64>() / n as f64 - 1.0;
+        deltas.iter_mut().for_each(|delta| *delta += inc);
+
+        // Keep the top players' rating changes from inflating.
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_unstable_by(|&a, &b| ratings[b].total_cmp(&ratings[a]));
+        let top = n.min(4 * (n as f64).sqrt().round() as usize);
+        let top_sum: f64 = order.iter().take(top).map(|&i| deltas[i]).sum();
+        let inc = (-top_sum / top as f64).clamp(-10.0, 0.0);
+        deltas.iter_mut().for_each(|delta| *delta += inc);
+
+        Ok(standings
+            .iter()
+            .enumerate()
+            .map(|(i, &(id, _, _))| {
+                let rating = ratings[i] + deltas[i];
+                self.players.insert(id, rating);
+                (id, perfs[i], rating)
+            })
+            .collect())
+    }
+
+    fn get_ratings(&self) -> Vec<(u64, f64)> {
+        self.players
+            .par_iter()
+            .map(|player| (*player.key(), *player.value()))
+            .collect()
+    }
+
+    fn get_rating_of(&self, id: &u64) -> Option<f64> {
+        self.players.get(id).map(|player| *player)
+    }
+}
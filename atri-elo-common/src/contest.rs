@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{standings, EloError, PlayerId, ScoreOrder, Standings};
+
+/// A contest as it is applied to the system.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContestRecord<Id = u64> {
+    /// The standings of an individual contest, empty for team contests.
+    pub standings: Standings<Id>,
+    /// The teams of a team contest, or `None` for an individual contest.
+    pub teams: Option<Vec<Vec<Id>>>,
+    /// The standings of a team contest, using indices into `teams` as ids, empty for individual contests.
+    #[serde(default)]
+    pub team_standings: Standings,
+    /// The time of the contest in seconds.
+    pub time: u64,
+    /// The weight of the contest, see [`ContestRecord::with_weight`].
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+    /// An external id of the contest, see [`ContestRecord::with_id`].
+    #[serde(default)]
+    pub id: Option<u64>,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl<Id: PlayerId> ContestRecord<Id> {
+    /// Construct an individual contest from its standings.
+    pub fn new(standings: Standings<Id>, time: u64) -> ContestRecord<Id> {
+        ContestRecord {
+            standings,
+            teams: None,
+            team_standings: Vec::new(),
+            time,
+            weight: default_weight(),
+            id: None,
+        }
+    }
+
+    /// Construct a team contest from its teams and the standings of their indices.
+    pub fn new_teams(teams: Vec<Vec<Id>>, standings: Standings, time: u64) -> ContestRecord<Id> {
+        ContestRecord {
+            teams: Some(teams),
+            team_standings: standings,
+            ..ContestRecord::new(Vec::new(), time)
+        }
+    }
+
+    /// Construct an individual contest from `(player_id, score)` pairs, ranked by the given score order.
+    pub fn from_scores<S: PartialOrd + Send>(
+        mut contest_scores: Vec<(Id, S)>,
+        order: ScoreOrder,
+        time: u64,
+    ) -> Result<ContestRecord<Id>, EloError<Id>> {
+        let standings = standings::from_scores(&mut contest_scores, order)?;
+        Ok(ContestRecord::new(standings, time))
+    }
+
+    /// Construct a team contest from `(members, score)` pairs, ranked by the given score order.
+    ///
+    /// Fails if a team is empty, or if a score cannot be compared, which is reported for the team's first member.
+    pub fn from_team_scores<S: PartialOrd + Send>(
+        team_scores: Vec<(Vec<Id>, S)>,
+        order: ScoreOrder,
+        time: u64,
+    ) -> Result<ContestRecord<Id>, EloError<Id>> {
+        for (index, (members, score)) in team_scores.iter().enumerate() {
+            let first = members.first().ok_or(EloError::EmptyTeam(index as u64))?;
+            if score.partial_cmp(score).is_none() {
+                return Err(EloError::InvalidScore(first.clone()));
+            }
+        }
+
+        let (teams, mut scores): (Vec<_>, Vec<_>) = team_scores
+            .into_iter()
+            .enumerate()
+            .map(|(index, (members, score))| (members, (index as u64, score)))
+            .unzip();
+        let standings = standings::rank_scores(&mut scores, order);
+        Ok(ContestRecord::new_teams(teams, standings, time))
+    }
+
+    /// Scale how much the contest counts.
+    ///
+    /// A contest of weight `w` is treated as if its performance noise were `β / sqrt(w)`, so that
+    /// its performances pull on ratings `w` times as much as usual.
+    /// Use a weight below 1 for low-stakes events and above 1 for major ones.
+    ///
+    /// The weight is 1 by default.
+    pub fn with_weight(mut self, weight: f64) -> ContestRecord<Id> {
+        self.weight = weight;
+        self
+    }
+
+    /// Tag the contest with an external id, e.g. the id it is stored under, to recognize it when it is replayed.
+    pub fn with_id(mut self, id: u64) -> ContestRecord<Id> {
+        self.id = Some(id);
+        self
+    }
+
+    /// Get the number of ranked participants, i.e. teams in a team contest.
+    pub(crate) fn len(&self) -> usize {
+        match self.teams {
+            Some(_) => self.team_standings.len(),
+            None => self.standings.len(),
+        }
+    }
+
+    /// Check whether the player took part in the contest.
+    pub(crate) fn involves(&self, id: &Id) -> bool {
+        match self.teams {
+            Some(ref teams) => teams.iter().any(|team| team.contains(id)),
+            None => self.standings.iter().any(|(other, _, _)| other == id),
+        }
+    }
+
+    /// Remove the player from the contest, dropping their team if it is left empty and closing the gap in the ranks.
+    pub(crate) fn remove_player(&mut self, id: &Id) {
+        match self.teams {
+            Some(ref mut teams) => {
+                for team in teams.iter_mut() {
+                    team.retain(|member| member != id);
+                }
+                if let Some(index) = teams.iter().position(|team| team.is_empty()) {
+                    teams.remove(index);
+                    let index = index as u64;
+                    self.team_standings.retain(|&(team, _, _)| team != index);
+                    for (team, _, _) in self.team_standings.iter_mut() {
+                        if *team > index {
+                            *team -= 1;
+                        }
+                    }
+                }
+                standings::rerank(&mut self.team_standings);
+            }
+            None => {
+                self.standings.retain(|(other, _, _)| other != id);
+                standings::rerank(&mut self.standings);
+            }
+        }
+    }
+}
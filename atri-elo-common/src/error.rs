@@ -0,0 +1,74 @@
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+};
+
+use crate::SolveError;
+
+/// An error returned when a rating system is misconfigured or a contest cannot be processed.
+///
+/// Players are identified by the id type of the rating system, `u64` by default.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EloError<Id = u64> {
+    /// The player appears more than once in the contest.
+    DuplicatePlayer(Id),
+    /// The contest has a single participant, so there is nothing to compare against.
+    NotEnoughPlayers,
+    /// The score of the player cannot be compared, e.g. a NaN.
+    InvalidScore(Id),
+    /// The ranks of the standings are inconsistent.
+    InvalidStandings,
+    /// The team at this index of a team contest has no members.
+    EmptyTeam(u64),
+    /// The weight of the contest is not a positive number.
+    InvalidWeight,
+    /// The player is not known to the system.
+    UnknownPlayer(Id),
+    /// The player's history cannot be replayed, since it was recorded without times, folded into the prior
+    /// or applied before the contest log was enabled.
+    IncompleteHistory(Id),
+    /// Contests cannot be replayed since the contest log is disabled.
+    MissingContestLog,
+    /// The named hyperparameter is out of its valid range.
+    InvalidParameter(&'static str),
+    /// An equation of the rating update could not be solved.
+    Solver(SolveError),
+    /// The rating update of the player produced a non-finite value.
+    NonFinite(Id),
+}
+
+impl<Id: Display> Display for EloError<Id> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EloError::DuplicatePlayer(id) => write!(f, "player {} appears more than once", id),
+            EloError::NotEnoughPlayers => write!(f, "contest has fewer than two players"),
+            EloError::InvalidScore(id) => write!(f, "score of player {} is not comparable", id),
+            EloError::InvalidStandings => write!(f, "standings have inconsistent ranks"),
+            EloError::EmptyTeam(index) => write!(f, "team {} has no members", index),
+            EloError::InvalidWeight => write!(f, "contest weight is not positive"),
+            EloError::UnknownPlayer(id) => write!(f, "player {} is unknown", id),
+            EloError::IncompleteHistory(id) => {
+                write!(f, "history of player {} has no times to replay", id)
+            }
+            EloError::MissingContestLog => write!(f, "contest log is disabled"),
+            EloError::InvalidParameter(name) => write!(f, "invalid hyperparameter {}", name),
+            EloError::Solver(err) => write!(f, "solver failed: {}", err),
+            EloError::NonFinite(id) => write!(f, "non-finite rating for player {}", id),
+        }
+    }
+}
+
+impl<Id: Debug + Display> Error for EloError<Id> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EloError::Solver(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<Id> From<SolveError> for EloError<Id> {
+    fn from(err: SolveError) -> Self {
+        EloError::Solver(err)
+    }
+}
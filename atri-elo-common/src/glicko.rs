@@ -0,0 +1,263 @@
+use std::{cmp::Ordering, f64::consts::PI};
+
+use dashmap::DashMap;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{standings, EloError, RatingSystem, SolveError};
+
+/// The factor converting Glicko ratings to the Glicko-2 scale.
+const SCALE: f64 = 173.7178;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Player {
+    mu: f64,
+    phi: f64,
+    sigma: f64,
+}
+
+/// An implementation of Glicko-2 algorithm.
+///
+/// Each contest is treated as a rating period in which every participant played a game against every other
+/// participant, winning against those ranked below, drawing with those tied and losing against those ranked above.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Glicko2 {
+    tau: f64,
+    rating_init: f64,
+    rd_init: f64,
+    volatility_init: f64,
+
+    players: DashMap<u64, Player>,
+}
+
+impl Default for Glicko2 {
+    fn default() -> Self {
+        Self::new(0.5, 1500.0, 350.0, 0.06)
+    }
+}
+
+impl Glicko2 {
+    /// Construct a new system.
+    ///
+    /// Default::default() gives a preset of superparameters (τ = 0.5, r_init = 1500, RD_init = 350, σ_init = 0.06).
+    ///
+    /// The superparameters are not checked here, see [`Glicko2::try_new`].
+    pub fn new(tau: f64, rating_init: f64, rd_init: f64, volatility_init: f64) -> Glicko2 {
+        Glicko2 {
+            tau,
+            rating_init,
+            rd_init,
+            volatility_init,
+            players: DashMap::new(),
+        }
+    }
+
+    /// Construct a new system, checking that the superparameters are valid.
+    pub fn try_new(
+        tau: f64,
+        rating_init: f64,
+        rd_init: f64,
+        volatility_init: f64,
+    ) -> Result<Glicko2, EloError> {
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+
+        if !positive(tau) {
+            return Err(EloError::InvalidParameter("tau"));
+        }
+        if !rating_init.is_finite() {
+            return Err(EloError::InvalidParameter("rating_init"));
+        }
+        if !positive(rd_init) {
+            return Err(EloError::InvalidParameter("rd_init"));
+        }
+        if !positive(volatility_init) {
+            return Err(EloError::InvalidParameter("volatility_init"));
+        }
+
+        Ok(Glicko2::new(tau, rating_init, rd_init, volatility_init))
+    }
+
+    /// Get the rating deviation of the specified player.
+    pub fn get_rd_of(&self, id: &u64) -> Option<f64> {
+        self.players.get(id).map(|player| player.phi * SCALE)
+    }
+
+    /// Get the volatility of the specified player.
+    pub fn get_volatility_of(&self, id: &u64) -> Option<f64> {
+        self.players.get(id).map(|player| player.sigma)
+    }
+}
+
+impl RatingSystem for Glicko2 {
+    type Error = EloError;
+
+    /// Update ratings according to the explicit standings of the provided contest.
+    ///
+    /// The contest time is ignored: only the participants' rating deviations grow between contests.
+    ///
+    /// The returned performance is the one-step estimate `μ + Δ` of the participant's strength in this contest.
+    fn update_standings(
+        &mut self,
+        mut standings: Vec<(u64, u64, u64)>,
+        _time: u64,
+    ) -> Result<Vec<(u64, f64, f64)>, EloError> {
+        if standings.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        standings::check(&mut standings)?;
+
+        let mut player_datas = Vec::with_capacity(standings.len());
+        standings
+            .par_iter()
+            .map(|(id, _, _)| {
+                let player = self.players.entry(*id).or_insert_with(|| Player {
+                    mu: 0.0,
+                    phi: self.rd_init / SCALE,
+                    sigma: self.volatility_init,
+                });
+                (player.mu, player.phi)
+            })
+            .collect_into_vec(&mut player_datas);
+
+        // Rate every participant before writing anything back, so that a failure leaves the system untouched.
+        let mut rated = Vec::with_capacity(standings.len());
+        standings
+            .par_iter()
+            .enumerate()
+            .map(|(i, &(id, lo, _))| {
+                let games: Vec<_> = standings
+                    .iter()
+                    .zip(player_datas.iter())
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, (&(_, lo_j, _), &(mu_j, phi_j)))| {
+                        let score = match lo.cmp(&lo_j) {
+                            Ordering::Less => 1.0,
+                            Ordering::Equal => 0.5,
+                            Ordering::Greater => 0.0,
+                        };
+                        (mu_j, phi_j, score)
+                    })
+                    .collect();
+
+                let player = self.players.get(&id).unwrap();
+                rate((player.mu, player.phi, player.sigma), self.tau, &games)
+            })
+            .collect_into_vec(&mut rated);
+        let rated = rated.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        Ok(standings
+            .iter()
+            .zip(rated)
+            .map(|(&(id, _, _), (mu, phi, sigma, perf))| {
+                let mut player = self.players.get_mut(&id).unwrap();
+                player.mu = mu;
+                player.phi = phi;
+                player.sigma = sigma;
+                (
+                    id,
+                    perf * SCALE + self.rating_init,
+                    mu * SCALE + self.rating_init,
+                )
+            })
+            .collect())
+    }
+
+    fn get_ratings(&self) -> Vec<(u64, f64)> {
+        self.players
+            .par_iter()
+            .map(|player| (*player.key(), player.mu * SCALE + self.rating_init))
+            .collect()
+    }
+
+    fn get_rating_of(&self, id: &u64) -> Option<f64> {
+        self.players
+            .get(id)
+            .map(|player| player.mu * SCALE + self.rating_init)
+    }
+}
+
+/// Run one Glicko-2 rating period on the Glicko-2 scale.
+///
+/// `games` follows `(opponent_mu, opponent_phi, score)` order.
+///
+/// Returns the new `(mu, phi, sigma)` and the performance estimate `mu + Δ`,
+/// or an error when the new volatility cannot be found within `MAX_ITERATIONS` steps.
+pub(crate) fn rate(
+    (mu, phi, sigma): (f64, f64, f64),
+    tau: f64,
+    games: &[(f64, f64, f64)],
+) -> Result<(f64, f64, f64, f64), SolveError> {
+    const EPSILON: f64 = 1e-6;
+    const MAX_ITERATIONS: usize = 100;
+
+    if games.is_empty() {
+        return Ok((mu, phi.hypot(sigma), sigma, mu));
+    }
+
+    let g = |phi: f64| (1.0 + 3.0 * phi.powi(2) / PI.powi(2)).sqrt().recip();
+
+    let mut v_inv = 0.0;
+    let mut score_sum = 0.0;
+    for &(mu_j, phi_j, s) in games {
+        let g_j = g(phi_j);
+        let e = (1.0 + (-g_j * (mu - mu_j)).exp()).recip();
+        v_inv += g_j.powi(2) * e * (1.0 - e);
+        score_sum += g_j * (s - e);
+    }
+    let v = v_inv.recip();
+    let delta = v * score_sum;
+
+    // Solve the new volatility with the Illinois algorithm.
+    let a = sigma.powi(2).ln();
+    let f = |x: f64| {
+        let e_x = x.exp();
+        e_x * (delta.powi(2) - phi.powi(2) - v - e_x) / (2.0 * (phi.powi(2) + v + e_x).powi(2))
+            - (x - a) / tau.powi(2)
+    };
+    let mut lo = a;
+    let mut hi = if delta.powi(2) > phi.powi(2) + v {
+        (delta.powi(2) - phi.powi(2) - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * tau) < 0.0 {
+            if k as usize == MAX_ITERATIONS {
+                return Err(SolveError::Bracket {
+                    a: a - k * tau,
+                    b: a,
+                });
+            }
+            k += 1.0;
+        }
+        a - k * tau
+    };
+    let mut f_lo = f(lo);
+    let mut f_hi = f(hi);
+    let mut iterations = 0;
+    while (hi - lo).abs() > EPSILON {
+        if iterations == MAX_ITERATIONS {
+            return Err(SolveError::NoConvergence);
+        }
+        iterations += 1;
+
+        let c = lo + (lo - hi) * f_lo / (f_hi - f_lo);
+        let f_c = f(c);
+        if f_c * f_hi <= 0.0 {
+            lo = hi;
+            f_lo = f_hi;
+        } else {
+            f_lo *= 0.5;
+        }
+        hi = c;
+        f_hi = f_c;
+    }
+    let sigma = (lo * 0.5).exp();
+
+    let phi_star = phi.hypot(sigma);
+    let phi = (phi_star.powi(-2) + v_inv).sqrt().recip();
+    let perf = mu + delta;
+    let mu = mu + phi.powi(2) * score_sum;
+
+    Ok((mu, phi, sigma, perf))
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{EloMmr, Player, PlayerId};
+
+/// What happens to players who stop competing, see [`EloMmr::with_inactivity_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InactivityPolicy {
+    /// Once a player has been idle for longer than `grace_secs`, each further second adds `variance_per_sec` to σ²,
+    /// which lowers their display rating and may make them provisional again.
+    IncreaseSigma {
+        grace_secs: u64,
+        variance_per_sec: f64,
+    },
+    /// Leave players out of [`EloMmr::get_ratings`] and [`EloMmr::get_display_ratings`] once they have been idle
+    /// for longer than `after_secs`.
+    Hide { after_secs: u64 },
+}
+
+impl<Id: PlayerId> EloMmr<Id> {
+    /// Apply the specified policy to players who stop competing.
+    ///
+    /// The policy is evaluated against the time set by [`EloMmr::set_time`] whenever ratings are read,
+    /// and never changes a player's state or performance history. When an inactive player competes again,
+    /// only [`EloMmr::with_drift_per_sec`] accounts for the time away.
+    ///
+    /// Inactive players are left as they are by default.
+    pub fn with_inactivity_policy(mut self, policy: InactivityPolicy) -> EloMmr<Id> {
+        self.inactivity = Some(policy);
+        self
+    }
+
+    /// Set the current time in seconds, against which the inactivity policy is evaluated.
+    pub fn set_time(&mut self, now: u64) {
+        self.now = Some(now);
+    }
+
+    /// Check whether the specified player has competed within the period of the inactivity policy.
+    pub fn is_active(&self, id: &Id) -> Option<bool> {
+        self.players.get(id).map(|player| !self.is_hidden(&player))
+    }
+
+    /// The time since the player's last contest, or 0 if either the current time or the player's last contest is unknown.
+    fn idle_secs(&self, player: &Player) -> u64 {
+        match (self.now, player.last_time) {
+            (Some(now), Some(last_time)) => now.saturating_sub(last_time),
+            _ => 0,
+        }
+    }
+
+    /// The uncertainty of the player's rating as displayed, after the inactivity policy.
+    pub(crate) fn inactive_sigma(&self, player: &Player) -> f64 {
+        match self.inactivity {
+            Some(InactivityPolicy::IncreaseSigma {
+                grace_secs,
+                variance_per_sec,
+            }) => {
+                let idle = self.idle_secs(player).saturating_sub(grace_secs);
+                (player.sigma.powi(2) + variance_per_sec * idle as f64).sqrt()
+            }
+            _ => player.sigma,
+        }
+    }
+
+    /// Whether the inactivity policy hides the player.
+    pub(crate) fn is_hidden(&self, player: &Player) -> bool {
+        match self.inactivity {
+            Some(InactivityPolicy::Hide { after_secs }) => self.idle_secs(player) > after_secs,
+            _ => false,
+        }
+    }
+}
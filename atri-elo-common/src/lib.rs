@@ -1,13 +1,115 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
 use dashmap::DashMap;
-use rayon::{
-    iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
-    slice::ParallelSliceMut,
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
 };
-use serde::{Deserialize, Serialize};
+use replay::LogEntry;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use solver::solve;
+use table::PerfTable;
+
+pub use breakdown::Breakdown;
+pub use codeforces::Codeforces;
+pub use contest::ContestRecord;
+pub use error::EloError;
+pub use glicko::Glicko2;
+pub use inactivity::InactivityPolicy;
+pub use replay::Replayed;
+pub use season::SeasonSnapshot;
+pub use simulation::{Simulation, SimulationReport};
+pub use snapshot::{Hyperparameters, SnapshotError, SnapshotHeader, SNAPSHOT_VERSION};
+pub use solver::{SolveError, SolverConfig, SolverMethod};
+pub use standings::{ScoreOrder, Standings};
+pub use system::RatingSystem;
+pub use topcoder::TopCoder;
+
+mod breakdown;
+
+mod codeforces;
+
+mod contest;
+
+mod error;
+
+mod glicko;
+
+mod inactivity;
+
+mod merge;
+
+mod predict;
+
+mod replay;
+
+mod season;
+
+mod simulation;
+
+mod snapshot;
+
+mod solver;
+
+mod standings;
+
+mod system;
+
+mod table;
+
+mod topcoder;
 
 #[cfg(test)]
 mod test;
 
+/// A type that identifies players, e.g. `u64` user ids or `String` usernames.
+///
+/// It is implemented for every type that satisfies its bounds.
+pub trait PlayerId: Clone + Eq + Hash + Send + Sync {}
+
+impl<T: Clone + Eq + Hash + Send + Sync> PlayerId for T {}
+
+/// COEFF = PI / sqrt(3)
+const COEFF: f64 = 1.8137993642342178;
+
+/// The likelihood used to update a player's rating from their performance history.
+///
+/// Both models estimate contest performances the same way, and differ in how past performances pull on the rating.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PerformanceModel {
+    /// Logistic likelihood, which is robust to outlier performances.
+    #[default]
+    Logistic,
+    /// Gaussian likelihood, which is cheaper to evaluate but lets outlier performances pull the rating further.
+    Gaussian,
+}
+
+/// How the members of a team are combined into a single competitor in team contests.
+///
+/// The team's prior is `w × Σ μ_i` with noise `w × sqrt(Σ δ_i²)`, where `w = 1 / n` for the mean of the members
+/// and `w = 1` for their sum. Once the team's performance `p` is solved, each member is credited with
+/// `μ_i + w × δ_i² / δ_team² × (p - μ_team)`, i.e. the team's surprise is split in proportion to the members' noise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TeamAggregation {
+    /// The team performs as the mean of its members, so teams of different sizes are on the same scale.
+    #[default]
+    Mean,
+    /// The team performs as the sum of its members, so larger teams are expected to do better.
+    Sum,
+}
+
+impl TeamAggregation {
+    fn weight(self, size: usize) -> f64 {
+        match self {
+            TeamAggregation::Mean => (size as f64).recip(),
+            TeamAggregation::Sum => 1.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Player {
     mu: f64,
@@ -17,6 +119,17 @@ struct Player {
 
     perfs: Vec<f64>,
     weights: Vec<f64>,
+
+    #[serde(default)]
+    last_time: Option<u64>,
+    #[serde(default)]
+    num_contests: usize,
+    /// The time and contest weight of each performance in `perfs[1..]`.
+    #[serde(default)]
+    timeline: Vec<(u64, f64)>,
+    /// The number of contests before each soft reset the player went through, and the shift it applied.
+    #[serde(default)]
+    resets: Vec<(usize, f64)>,
 }
 
 impl Player {
@@ -28,6 +141,10 @@ impl Player {
             delta: 0.0,
             perfs: vec![mu],
             weights: vec![sigma.powi(-2)],
+            last_time: None,
+            num_contests: 0,
+            timeline: Vec::new(),
+            resets: Vec::new(),
         }
     }
 
@@ -45,216 +162,878 @@ impl Player {
         self.sigma /= kappa.sqrt();
     }
 
-    fn update(
+    fn update<Id: PlayerId>(
         &mut self,
-        beta: f64,
+        system: &EloMmr<Id>,
+        weight: f64,
         player_data: &[(f64, f64)],
+        table: Option<&PerfTable>,
         (lo, hi): (u64, u64),
-    ) -> (f64, f64) {
-        // COEFF = PI / sqrt(3)
-        const COEFF: f64 = 1.8137993642342178;
-        const SOLVE_BOUND: (f64, f64) = (-10000.0, 10000.0);
-
-        let f = |x: f64| {
-            let mut result = 0.0;
-            for &(delta, mu_pi) in player_data.iter().skip(lo as usize - 1) {
-                result += delta.recip() * ((COEFF * (x - mu_pi) / (2.0 * delta)).tanh() - 1.0);
-            }
-            for &(delta, mu_pi) in player_data.iter().take(hi as usize) {
-                result += delta.recip() * ((COEFF * (x - mu_pi) / (2.0 * delta)).tanh() + 1.0);
-            }
-            result
-        };
+    ) -> Result<(f64, f64), SolveError> {
+        let perf = performance(
+            (self.delta, self.mu_pi),
+            system,
+            player_data,
+            table,
+            (lo, hi),
+        )?;
+        let rating = self.add_performance(system, weight, perf)?;
+        Ok((perf, rating))
+    }
 
-        let perf = solve_itp(SOLVE_BOUND, f);
+    /// Add a performance from a contest of the given weight, which counts as `weight / β²` of evidence.
+    fn add_performance<Id: PlayerId>(
+        &mut self,
+        system: &EloMmr<Id>,
+        weight: f64,
+        perf: f64,
+    ) -> Result<f64, SolveError> {
+        let EloMmr {
+            beta,
+            model,
+            max_history,
+            ref solver,
+            ..
+        } = *system;
 
+        let evidence = weight * beta.powi(-2);
+        if self.timeline.len() + 1 == self.perfs.len() {
+            self.timeline.push((self.last_time.unwrap_or(0), weight));
+        }
         self.perfs.push(perf);
-        self.weights.push(beta.powi(-2));
+        self.weights.push(evidence);
+        self.sigma = (self.sigma.powi(-2) + evidence).sqrt().recip();
+        self.num_contests += 1;
+
+        // Fold the oldest performances into the prior term.
+        if let Some(max_history) = max_history {
+            while self.perfs.len() > max_history + 1 {
+                let (w_0, w_1) = (self.weights[0], self.weights[1]);
+                self.perfs[0] = (w_0 * self.perfs[0] + w_1 * self.perfs[1]) / (w_0 + w_1);
+                self.weights[0] = w_0 + w_1;
+                if self.timeline.len() + 1 == self.perfs.len() {
+                    self.timeline.remove(0);
+                }
+                self.perfs.remove(1);
+                self.weights.remove(1);
+            }
+        }
 
         let f = |x: f64| {
             let mut result = 0.0;
+            let mut derivative = 0.0;
             result += self.weights[0] * (x - self.perfs[0]);
+            derivative += self.weights[0];
             for k in 1..self.perfs.len() {
-                result += (COEFF * beta * self.weights[k])
-                    * (COEFF * (x - self.perfs[k]) / (2.0 * beta)).tanh();
+                match model {
+                    PerformanceModel::Logistic => {
+                        let t = (COEFF * (x - self.perfs[k]) / (2.0 * beta)).tanh();
+                        result += (COEFF * beta * self.weights[k]) * t;
+                        derivative += 0.5 * COEFF * COEFF * self.weights[k] * (1.0 - t * t);
+                    }
+                    PerformanceModel::Gaussian => {
+                        result += self.weights[k] * (x - self.perfs[k]);
+                        derivative += self.weights[k];
+                    }
+                }
             }
-            result
+            (result, derivative)
         };
 
-        self.mu = solve_itp(SOLVE_BOUND, f);
+        self.mu = solve(self.mu, beta, f, solver)?;
 
-        (perf, self.mu)
+        Ok(self.mu)
     }
+
+    fn state(&self) -> PlayerState {
+        PlayerState {
+            mu: self.mu,
+            sigma: self.sigma,
+            num_contests: self.num_contests,
+            perfs: self.perfs.clone(),
+            weights: self.weights.clone(),
+        }
+    }
+}
+
+/// Solve the performance of a participant with prior `(delta, mu_pi)` ranked in `lo..=hi`.
+///
+/// `player_data` holds every participant's `(delta, mu_pi)` in rank order.
+fn performance<Id: PlayerId>(
+    (delta, mu_pi): (f64, f64),
+    system: &EloMmr<Id>,
+    player_data: &[(f64, f64)],
+    table: Option<&PerfTable>,
+    (lo, hi): (u64, u64),
+) -> Result<f64, SolveError> {
+    let f = |x: f64| {
+        let mut result = 0.0;
+        let mut derivative = 0.0;
+        for &(delta, mu_pi) in player_data.iter().skip(lo as usize - 1) {
+            let t = (COEFF * (x - mu_pi) / (2.0 * delta)).tanh();
+            result += delta.recip() * (t - 1.0);
+            derivative += COEFF * (1.0 - t * t) / (2.0 * delta * delta);
+        }
+        for &(delta, mu_pi) in player_data.iter().take(hi as usize) {
+            let t = (COEFF * (x - mu_pi) / (2.0 * delta)).tanh();
+            result += delta.recip() * (t + 1.0);
+            derivative += COEFF * (1.0 - t * t) / (2.0 * delta * delta);
+        }
+        (result, derivative)
+    };
+
+    // Search around the prior rating, on the scale of the performance noise.
+    match table {
+        Some(table) => solve(
+            mu_pi,
+            delta,
+            |x| table.perf_equation(x, player_data, (lo, hi)),
+            &system.solver,
+        ),
+        None => solve(mu_pi, delta, f, &system.solver),
+    }
+}
+
+/// A read-only snapshot of a player's state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerState {
+    /// The rating of the player.
+    pub mu: f64,
+    /// The uncertainty of the rating.
+    pub sigma: f64,
+    /// The number of contests the player has taken part in.
+    pub num_contests: usize,
+    /// The performance history of the player.
+    ///
+    /// The first entry is the prior term, the rest are contest performances in chronological order.
+    /// If the history is bounded, older performances are folded into the prior term.
+    pub perfs: Vec<f64>,
+    /// The weights corresponding to `perfs`.
+    pub weights: Vec<f64>,
+}
+
+/// The participants' states right before a contest was applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Checkpoint<Id> {
+    contest: ContestRecord<Id>,
+    players: Vec<(Id, Option<Player>)>,
 }
 
 /// An implementation of EloMMR algorithm.
+///
+/// Players are identified by `u64` ids by default, or by any other [`PlayerId`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EloMmr {
+#[serde(bound(serialize = "Id: Serialize", deserialize = "Id: Deserialize<'de>"))]
+pub struct EloMmr<Id: PlayerId = u64> {
     rho: f64,
     beta: f64,
     gamma: f64,
     mu_init: f64,
     sigma_init: f64,
+    #[serde(default)]
+    drift_per_sec: f64,
+    #[serde(default)]
+    model: PerformanceModel,
+    #[serde(default)]
+    max_history: Option<usize>,
+    #[serde(default)]
+    approximation: Option<(usize, f64)>,
+    #[serde(default)]
+    solver: SolverConfig,
+    #[serde(default)]
+    team_aggregation: TeamAggregation,
+    #[serde(default)]
+    display_sigma_factor: f64,
+    #[serde(default)]
+    provisional: Option<(f64, usize)>,
+    #[serde(default)]
+    inactivity: Option<InactivityPolicy>,
+    #[serde(default)]
+    now: Option<u64>,
+    #[serde(default)]
+    seasons: Vec<SeasonSnapshot<Id>>,
+    #[serde(default)]
+    contest_log: Option<Vec<LogEntry<Id>>>,
+    #[serde(default)]
+    contests_applied: u64,
 
-    players: DashMap<u64, Player>,
+    players: DashMap<Id, Player>,
+
+    #[serde(default)]
+    checkpoint_limit: usize,
+    #[serde(default)]
+    checkpoints: VecDeque<Checkpoint<Id>>,
 }
 
-impl Default for EloMmr {
+impl<Id: PlayerId> Default for EloMmr<Id> {
     fn default() -> Self {
         Self::new(1.0, 200.0, 80.0, 1500.0, 350.0)
     }
 }
 
-impl EloMmr {
+impl<Id: PlayerId> EloMmr<Id> {
     /// Construct a new system.
     ///
     /// Default::default() gives a preset of superparameters (ρ = 1, β = 200, γ = 80, μ_init = 1500, σ_init = 350).
-    pub fn new(rho: f64, beta: f64, gamma: f64, mu_init: f64, sigma_init: f64) -> EloMmr {
+    ///
+    /// The superparameters are not checked here, see [`EloMmr::try_new`].
+    pub fn new(rho: f64, beta: f64, gamma: f64, mu_init: f64, sigma_init: f64) -> EloMmr<Id> {
         EloMmr {
             rho,
             beta,
             gamma,
             mu_init,
             sigma_init,
+            drift_per_sec: 0.0,
+            model: PerformanceModel::Logistic,
+            max_history: None,
+            approximation: None,
+            solver: SolverConfig::default(),
+            team_aggregation: TeamAggregation::Mean,
+            display_sigma_factor: 0.0,
+            provisional: None,
+            inactivity: None,
+            now: None,
+            seasons: Vec::new(),
+            contest_log: None,
+            contests_applied: 0,
             players: DashMap::new(),
+            checkpoint_limit: 0,
+            checkpoints: VecDeque::new(),
+        }
+    }
+
+    /// Construct a new system, checking that the superparameters are valid.
+    pub fn try_new(
+        rho: f64,
+        beta: f64,
+        gamma: f64,
+        mu_init: f64,
+        sigma_init: f64,
+    ) -> Result<EloMmr<Id>, EloError<Id>> {
+        let system = EloMmr::new(rho, beta, gamma, mu_init, sigma_init);
+        system.validate()?;
+        Ok(system)
+    }
+
+    /// Check that the superparameters and configuration of the system are valid.
+    pub fn validate(&self) -> Result<(), EloError<Id>> {
+        self.check_parameters().map_err(EloError::InvalidParameter)
+    }
+
+    /// Check the superparameters and configuration, returning the name of the first invalid one.
+    fn check_parameters(&self) -> Result<(), &'static str> {
+        let non_negative = |x: f64| x.is_finite() && x >= 0.0;
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+
+        if !non_negative(self.rho) {
+            return Err("rho");
+        }
+        if !positive(self.beta) {
+            return Err("beta");
+        }
+        if !non_negative(self.gamma) {
+            return Err("gamma");
+        }
+        if !self.mu_init.is_finite() {
+            return Err("mu_init");
+        }
+        if !positive(self.sigma_init) {
+            return Err("sigma_init");
+        }
+        if !non_negative(self.drift_per_sec) {
+            return Err("drift_per_sec");
+        }
+        if let Some((_, resolution)) = self.approximation {
+            if !positive(resolution) {
+                return Err("resolution");
+            }
+        }
+        if !positive(self.solver.tolerance) {
+            return Err("tolerance");
+        }
+        if self.solver.max_iterations == 0 {
+            return Err("max_iterations");
+        }
+        if !self.display_sigma_factor.is_finite() {
+            return Err("sigma_factor");
+        }
+        if let Some((max_sigma, _)) = self.provisional {
+            if max_sigma.is_nan() || max_sigma < 0.0 {
+                return Err("max_sigma");
+            }
+        }
+        if let Some(InactivityPolicy::IncreaseSigma {
+            variance_per_sec, ..
+        }) = self.inactivity
+        {
+            if !non_negative(variance_per_sec) {
+                return Err("variance_per_sec");
+            }
+        }
+        Ok(())
+    }
+
+    /// Use the specified performance model to update ratings.
+    ///
+    /// The logistic model is used by default.
+    pub fn with_performance_model(mut self, model: PerformanceModel) -> EloMmr<Id> {
+        self.model = model;
+        self
+    }
+
+    /// Keep at most `max_history` performances per player, folding older ones into the prior term.
+    ///
+    /// This bounds the memory and time an update takes for very active players, at the cost of treating
+    /// old performances as Gaussian evidence. The history is unbounded by default.
+    pub fn with_max_history(mut self, max_history: usize) -> EloMmr<Id> {
+        self.max_history = Some(max_history);
+        self
+    }
+
+    /// Use the specified solver configuration for performance and rating equations.
+    pub fn with_solver(mut self, solver: SolverConfig) -> EloMmr<Id> {
+        self.solver = solver;
+        self
+    }
+
+    /// Approximate the performances of contests with at least `min_players` participants.
+    ///
+    /// Solving the exact performance equation costs O(n) per participant, so a contest costs O(n²).
+    /// Instead, the contest-wide part of the equation is tabulated once with cubic Hermite interpolation
+    /// every `resolution` rating points, which makes a contest cost about O(n · range / resolution + n).
+    /// Large ties, e.g. many zero scores, are tabulated the same way, so they do not bring back the O(n²) cost.
+    /// With `resolution = 10` on the default scale, performances stay within 1e-3 of the exact ones.
+    ///
+    /// Contests are solved exactly by default.
+    pub fn with_approximation(mut self, min_players: usize, resolution: f64) -> EloMmr<Id> {
+        self.approximation = Some((min_players, resolution));
+        self
+    }
+
+    /// Make the diffusion between contests depend on the time elapsed since the player's last contest.
+    ///
+    /// Each diffusion adds `γ² + drift_per_sec × elapsed_secs` to the player's variance, so returning players
+    /// get more uncertainty than regular ones. Lower γ accordingly to make frequent players receive less noise.
+    ///
+    /// The drift is disabled (`drift_per_sec = 0`) by default.
+    pub fn with_drift_per_sec(mut self, drift_per_sec: f64) -> EloMmr<Id> {
+        self.drift_per_sec = drift_per_sec;
+        self
+    }
+
+    /// Use the specified aggregation to combine team members in team contests.
+    ///
+    /// Teams perform as the mean of their members by default.
+    pub fn with_team_aggregation(mut self, team_aggregation: TeamAggregation) -> EloMmr<Id> {
+        self.team_aggregation = team_aggregation;
+        self
+    }
+
+    /// Display ratings as `μ - sigma_factor × σ`, a conservative estimate that new and returning players have to earn.
+    ///
+    /// This only affects [`EloMmr::get_display_ratings`] and [`EloMmr::get_display_rating_of`].
+    /// Raw ratings are displayed (`sigma_factor = 0`) by default.
+    pub fn with_display_rating(mut self, sigma_factor: f64) -> EloMmr<Id> {
+        self.display_sigma_factor = sigma_factor;
+        self
+    }
+
+    /// Mark players as provisional while their σ is above `max_sigma` or they have taken part in fewer than
+    /// `min_contests` contests.
+    ///
+    /// No player is provisional by default.
+    pub fn with_provisional(mut self, max_sigma: f64, min_contests: usize) -> EloMmr<Id> {
+        self.provisional = Some((max_sigma, min_contests));
+        self
+    }
+
+    /// Keep checkpoints of the last `limit` contests so that they can be rolled back.
+    ///
+    /// Checkpointing is disabled (`limit = 0`) by default.
+    pub fn with_checkpoint_limit(mut self, limit: usize) -> EloMmr<Id> {
+        self.checkpoint_limit = limit;
+        while self.checkpoints.len() > limit {
+            self.checkpoints.pop_front();
         }
+        self
     }
 
-    /// Update ratings according to the result of the provided contest.
+    /// Get the number of contests that can currently be rolled back.
+    pub fn num_checkpoints(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Get the number of contests applied to the system, not counting rolled back ones.
+    pub fn contests_applied(&self) -> u64 {
+        self.contests_applied
+    }
+
+    /// Undo the last applied contest, restoring its participants to their previous states.
+    ///
+    /// Returns the undone contest, so that it can be corrected and applied again with [`EloMmr::apply`].
+    ///
+    /// Returns `None` if there is no checkpoint left.
+    pub fn rollback(&mut self) -> Option<ContestRecord<Id>> {
+        let checkpoint = self.checkpoints.pop_back()?;
+        if let Some(ref mut log) = self.contest_log {
+            log.pop();
+        }
+        self.contests_applied = self.contests_applied.saturating_sub(1);
+        for (id, player) in checkpoint.players {
+            match player {
+                Some(player) => {
+                    self.players.insert(id, player);
+                }
+                None => {
+                    self.players.remove(&id);
+                }
+            }
+        }
+        Some(checkpoint.contest)
+    }
+
+    /// Update ratings according to the result of the provided contest, where a higher score is better.
+    ///
+    /// Scores can be of any comparable type, e.g. `i64` total scores or `f64` accuracies.
+    ///
+    /// See [`EloMmr::apply`] for details.
+    pub fn update<S: PartialOrd + Send>(
+        &mut self,
+        contest_scores: Vec<(Id, S)>,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        self.update_with_order(contest_scores, ScoreOrder::HigherIsBetter, time)
+    }
+
+    /// Update ratings according to the result of the provided contest, ranked by the given score order.
+    ///
+    /// See [`EloMmr::apply`] for details.
+    pub fn update_with_order<S: PartialOrd + Send>(
+        &mut self,
+        contest_scores: Vec<(Id, S)>,
+        order: ScoreOrder,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        self.apply(ContestRecord::from_scores(contest_scores, order, time)?)
+    }
+
+    /// Update ratings according to the explicit standings of the provided contest.
+    ///
+    /// `standings` follows `(player_id, lo, hi)` order, where `lo..=hi` is the range of ranks shared by tied players,
+    /// e.g. `[(a, 1, 1), (b, 2, 3), (c, 2, 3)]` when `b` and `c` tie for the second place.
+    ///
+    /// See [`EloMmr::apply`] for details.
+    pub fn update_standings(
+        &mut self,
+        standings: Standings<Id>,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        self.apply(ContestRecord::new(standings, time))
+    }
+
+    /// Update ratings according to the result of the provided team contest, where a higher score is better.
+    ///
+    /// `team_scores` follows `(members, score)` order.
+    ///
+    /// See [`EloMmr::apply`] for details.
+    pub fn update_teams<S: PartialOrd + Send>(
+        &mut self,
+        team_scores: Vec<(Vec<Id>, S)>,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        self.apply(ContestRecord::from_team_scores(
+            team_scores,
+            ScoreOrder::HigherIsBetter,
+            time,
+        )?)
+    }
+
+    /// Update ratings according to the explicit standings of the provided team contest.
     ///
-    /// If contest scores are empty, this function will return an empty Vec.
+    /// `standings` ranks the teams, using their indices into `teams` as ids.
     ///
-    /// Returns the partcipants' performance and rating.
+    /// See [`EloMmr::apply`] for details.
+    pub fn update_team_standings(
+        &mut self,
+        teams: Vec<Vec<Id>>,
+        standings: Standings,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        self.apply(ContestRecord::new_teams(teams, standings, time))
+    }
+
+    /// Update ratings according to the provided contest.
+    ///
+    /// In a team contest, each team competes as a single participant combined by the configured
+    /// [`TeamAggregation`], and its performance is then split among its members, who are all rated individually.
+    ///
+    /// The weight of the contest scales how much the new performances count, see [`ContestRecord::with_weight`].
+    ///
+    /// If standings are empty, this function will return an empty Vec.
+    ///
+    /// Returns the partcipants' performance and rating, or an error if the system is misconfigured,
+    /// the contest is malformed or the update fails, in which case no rating is changed.
     ///
     /// The returned tuple follows `(player_id, perf, rating)` order.
-    pub fn update(&self, mut contest_scores: Vec<(u64, i64)>) -> Vec<(u64, f64, f64)> {
-        if contest_scores.is_empty() {
-            return Vec::new();
-        }
-
-        // Calculate standings for internal use.
-        let mut standings = Vec::new();
-        let raw = &mut contest_scores;
-        raw.par_sort_unstable_by_key(|v| -v.1);
-        let mut rank_app = 1u64;
-        let mut rank_int = 1u64;
-        standings.push((raw[0].0, 1, 0));
-        for (i, (id, score)) in raw.iter().enumerate().skip(1) {
-            rank_int += 1;
-            if *score != raw[i - 1].1 {
-                rank_app = rank_int;
+    ///
+    /// If checkpointing is enabled, a checkpoint of the participants is kept for [`EloMmr::rollback`].
+    pub fn apply(
+        &mut self,
+        contest: ContestRecord<Id>,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        let result = self.apply_inner(contest, false)?;
+        Ok(result
+            .into_iter()
+            .map(|(id, breakdown)| (id, breakdown.perf, breakdown.rating))
+            .collect())
+    }
+
+    /// Update ratings according to the provided contest, explaining each participant's new performance and rating.
+    ///
+    /// Collecting the terms costs O(n) per participant even if the contest is approximated.
+    ///
+    /// See [`EloMmr::apply`] for details.
+    pub fn apply_with_breakdown(
+        &mut self,
+        contest: ContestRecord<Id>,
+    ) -> Result<Vec<(Id, Breakdown<Id>)>, EloError<Id>> {
+        self.apply_inner(contest, true)
+    }
+
+    /// Apply the contest, leaving the terms of the breakdowns empty unless `explain` is set.
+    fn apply_inner(
+        &mut self,
+        mut contest: ContestRecord<Id>,
+        explain: bool,
+    ) -> Result<Vec<(Id, Breakdown<Id>)>, EloError<Id>> {
+        if contest.len() == 0 {
+            return Ok(Vec::new());
+        }
+
+        self.validate()?;
+        if !contest.weight.is_finite() || contest.weight <= 0.0 {
+            return Err(EloError::InvalidWeight);
+        }
+
+        match contest.teams {
+            Some(ref teams) => {
+                standings::check_teams(teams, &mut contest.team_standings)?;
+                self.apply_teams(contest, explain)
+            }
+            None => {
+                standings::check(&mut contest.standings)?;
+                self.apply_individual(contest, explain)
             }
-            standings.push((*id, rank_app, 0));
         }
-        standings.last_mut().unwrap().2 = rank_app;
-        for (i, (_, score)) in raw.iter().enumerate().rev().skip(1) {
-            rank_int -= 1;
-            if *score != raw[i + 1].1 {
-                rank_app = rank_int;
+    }
+
+    fn apply_individual(
+        &mut self,
+        contest: ContestRecord<Id>,
+        explain: bool,
+    ) -> Result<Vec<(Id, Breakdown<Id>)>, EloError<Id>> {
+        let ContestRecord {
+            ref standings,
+            time,
+            weight,
+            ..
+        } = contest;
+
+        let ids: Vec<Id> = standings.iter().map(|(id, _, _)| id.clone()).collect();
+        let mut players = self.prepare_players(&ids, time, weight);
+
+        let player_datas: Vec<_> = players
+            .iter()
+            .map(|player| (player.delta, player.mu_pi))
+            .collect();
+
+        let table = match self.approximation {
+            Some((min_players, resolution)) if standings.len() >= min_players => {
+                let ranks = standings.iter().map(|&(_, lo, hi)| (lo, hi));
+                Some(PerfTable::new(&player_datas, ranks, resolution))
             }
-            standings[i].2 = rank_app;
+            _ => None,
+        };
+
+        let result = standings
+            .par_iter()
+            .zip(players.par_iter_mut())
+            .map(|((id, lo, hi), player)| {
+                let (perf, rating) =
+                    player.update(self, weight, &player_datas, table.as_ref(), (*lo, *hi))?;
+                if !perf.is_finite() || !rating.is_finite() || !player.sigma.is_finite() {
+                    return Err(EloError::NonFinite(id.clone()));
+                }
+                Ok((
+                    id.clone(),
+                    self.breakdown(explain, player, perf, (&ids, &player_datas), (*lo, *hi)),
+                ))
+            })
+            .collect::<Result<Vec<_>, EloError<Id>>>()?;
+
+        self.commit(contest, ids.into_iter().zip(players));
+
+        Ok(result)
+    }
+
+    fn apply_teams(
+        &mut self,
+        contest: ContestRecord<Id>,
+        explain: bool,
+    ) -> Result<Vec<(Id, Breakdown<Id>)>, EloError<Id>> {
+        let ContestRecord {
+            team_standings: ref standings,
+            ref teams,
+            time,
+            weight,
+            ..
+        } = contest;
+        let teams = teams.as_ref().unwrap();
+
+        let ids: Vec<Id> = standings
+            .iter()
+            .flat_map(|&(team, _, _)| teams[team as usize].iter().cloned())
+            .collect();
+        let mut players = self.prepare_players(&ids, time, weight);
+
+        // Combine the members of each team into a single participant.
+        let mut team_datas = Vec::with_capacity(standings.len());
+        let mut team_weights = Vec::with_capacity(ids.len());
+        let mut start = 0;
+        for &(team, _, _) in standings {
+            let members = &players[start..start + teams[team as usize].len()];
+            start += members.len();
+            let w = self.team_aggregation.weight(members.len());
+            let mu_pi = w * members.iter().map(|player| player.mu_pi).sum::<f64>();
+            let delta = w * members
+                .iter()
+                .map(|player| player.delta.powi(2))
+                .sum::<f64>()
+                .sqrt();
+            team_datas.push((delta, mu_pi));
+            team_weights.extend(members.iter().map(|_| (team_datas.len() - 1, w)));
         }
 
-        // Calculate new ratings.
-        let mut player_datas = Vec::with_capacity(standings.len());
-        standings
+        let table = match self.approximation {
+            Some((min_players, resolution)) if standings.len() >= min_players => {
+                let ranks = standings.iter().map(|&(_, lo, hi)| (lo, hi));
+                Some(PerfTable::new(&team_datas, ranks, resolution))
+            }
+            _ => None,
+        };
+
+        let team_perfs = standings
             .par_iter()
-            .map(|(id, _, _)| {
-                let mut player = self
-                    .players
-                    .entry(*id)
-                    .or_insert_with(|| Player::new(self.mu_init, self.sigma_init));
-                player.diffuse(self.rho, self.gamma);
-                player.mu_pi = player.mu;
-                player.delta = player.sigma.hypot(self.beta);
-                (player.delta, player.mu_pi)
+            .zip(team_datas.par_iter())
+            .map(|(&(_, lo, hi), &team_data)| {
+                performance(team_data, self, &team_datas, table.as_ref(), (lo, hi))
             })
-            .collect_into_vec(&mut player_datas);
+            .collect::<Result<Vec<_>, SolveError>>()?;
 
-        let mut result = Vec::with_capacity(standings.len());
-        standings
+        // Teams are identified by their first member.
+        let team_ids: Vec<Id> = standings
+            .iter()
+            .map(|&(team, _, _)| teams[team as usize][0].clone())
+            .collect();
+        let result = ids
             .par_iter()
-            .map(|&(id, lo, hi)| {
-                let mut player = self.players.get_mut(&id).unwrap();
-                let (perf, rating) = player.update(self.beta, &player_datas, (lo, hi));
-                (id, perf, rating)
+            .zip(players.par_iter_mut())
+            .zip(team_weights.par_iter())
+            .map(|((id, player), &(k, w))| {
+                let (delta, mu_pi) = team_datas[k];
+                let perf = player.mu_pi
+                    + w * player.delta.powi(2) / delta.powi(2) * (team_perfs[k] - mu_pi);
+                let rating = player.add_performance(self, weight, perf)?;
+                if !perf.is_finite() || !rating.is_finite() || !player.sigma.is_finite() {
+                    return Err(EloError::NonFinite(id.clone()));
+                }
+                let (_, lo, hi) = standings[k];
+                let mut breakdown = self.breakdown(
+                    explain,
+                    player,
+                    team_perfs[k],
+                    (&team_ids, &team_datas),
+                    (lo, hi),
+                );
+                breakdown.perf = perf;
+                Ok((id.clone(), breakdown))
+            })
+            .collect::<Result<Vec<_>, EloError<Id>>>()?;
+
+        self.commit(contest, ids.into_iter().zip(players));
+
+        Ok(result)
+    }
+
+    /// Explain the update of a participant if asked to, see [`Breakdown::new`].
+    fn breakdown(
+        &self,
+        explain: bool,
+        player: &Player,
+        perf: f64,
+        participants: (&[Id], &[(f64, f64)]),
+        ranks: (u64, u64),
+    ) -> Breakdown<Id> {
+        if explain {
+            Breakdown::new(self, player, perf, participants, ranks)
+        } else {
+            Breakdown {
+                perf,
+                rating: player.mu,
+                opponents: Vec::new(),
+                prior: 0.0,
+                history: Vec::new(),
+            }
+        }
+    }
+
+    /// Copy the participants of a contest and diffuse them up to `time`.
+    ///
+    /// New ratings are calculated on the copies, so that a failure leaves the system untouched.
+    /// The performance noise of a contest of weight `w` is `β / sqrt(w)`.
+    fn prepare_players(&self, ids: &[Id], time: u64, weight: f64) -> Vec<Player> {
+        let mut players = Vec::with_capacity(ids.len());
+        ids.par_iter()
+            .map(|id| {
+                let mut player = self.players.get(id).map_or_else(
+                    || Player::new(self.mu_init, self.sigma_init),
+                    |player| player.clone(),
+                );
+                self.diffuse_until(&mut player, time);
+                player.mu_pi = player.mu;
+                player.delta = player.sigma.hypot(self.beta / weight.sqrt());
+                player
             })
-            .collect_into_vec(&mut result);
+            .collect_into_vec(&mut players);
+        players
+    }
+
+    /// Diffuse the player from their last contest until a contest at `time`.
+    fn diffuse_until(&self, player: &mut Player, time: u64) {
+        let elapsed = player
+            .last_time
+            .map_or(0, |last_time| time.saturating_sub(last_time));
+        let gamma = (self.gamma.powi(2) + self.drift_per_sec * elapsed as f64).sqrt();
+        player.diffuse(self.rho, gamma);
+        player.last_time = Some(time);
+    }
+
+    /// Store the updated participants, saving their previous states for rollback.
+    fn commit(&mut self, contest: ContestRecord<Id>, players: impl Iterator<Item = (Id, Player)>) {
+        let players: Vec<_> = players.collect();
+        if let Some(ref mut log) = self.contest_log {
+            log.push(LogEntry::Contest(contest.clone()));
+        }
+        self.contests_applied += 1;
+        if self.checkpoint_limit > 0 {
+            let previous = players
+                .iter()
+                .map(|(id, _)| {
+                    (
+                        id.clone(),
+                        self.players.get(id).map(|player| player.clone()),
+                    )
+                })
+                .collect();
+            if self.checkpoints.len() == self.checkpoint_limit {
+                self.checkpoints.pop_front();
+            }
+            self.checkpoints.push_back(Checkpoint {
+                contest,
+                players: previous,
+            });
+        }
 
-        result
+        for (id, player) in players {
+            self.players.insert(id, player);
+        }
     }
 
-    /// Get all players' rating.
+    /// Get all players' rating, except those hidden by the inactivity policy.
     ///
     /// The returned tuple follows `(player_id, rating)` order.
-    pub fn get_ratings(&self) -> Vec<(u64, f64)> {
+    pub fn get_ratings(&self) -> Vec<(Id, f64)> {
         self.players
             .par_iter()
-            .map(|player| (*player.key(), player.mu))
+            .filter(|player| !self.is_hidden(player))
+            .map(|player| (player.key().clone(), player.mu))
             .collect()
     }
 
     /// Get the rating of the specified player.
-    pub fn get_rating_of(&self, id: &u64) -> Option<f64> {
+    pub fn get_rating_of(&self, id: &Id) -> Option<f64> {
         self.players.get(id).map(|player| player.mu)
     }
-}
 
-/// Solve f(x) = 0 where x belongs to [a, b].
-///
-/// Panics when `a < b` or `f(a) < 0 < f(b)` is not satisfied.
-fn solve_itp((mut a, mut b): (f64, f64), mut f: impl FnMut(f64) -> f64) -> f64 {
-    const EPSILON: f64 = 1e-10;
-    const N_0: usize = 1;
+    /// Get display ratings of all players, except those hidden by the inactivity policy.
+    ///
+    /// See [`EloMmr::with_display_rating`] and [`EloMmr::with_inactivity_policy`].
+    pub fn get_display_ratings(&self) -> Vec<(Id, f64)> {
+        self.players
+            .par_iter()
+            .filter(|player| !self.is_hidden(player))
+            .map(|player| (player.key().clone(), self.display_rating(&player)))
+            .collect()
+    }
 
-    debug_assert!(a < b);
+    /// Get the display rating of the specified player.
+    pub fn get_display_rating_of(&self, id: &Id) -> Option<f64> {
+        self.players
+            .get(id)
+            .map(|player| self.display_rating(&player))
+    }
 
-    let mut y_a = f(a);
-    let mut y_b = f(b);
+    fn display_rating(&self, player: &Player) -> f64 {
+        player.mu - self.display_sigma_factor * self.inactive_sigma(player)
+    }
+
+    /// Check whether the specified player is provisional.
+    ///
+    /// See [`EloMmr::with_provisional`].
+    pub fn is_provisional(&self, id: &Id) -> Option<bool> {
+        self.players.get(id).map(|player| match self.provisional {
+            Some((max_sigma, min_contests)) => {
+                self.inactive_sigma(&player) > max_sigma || player.num_contests < min_contests
+            }
+            None => false,
+        })
+    }
 
-    if y_a.abs() < EPSILON {
-        return a;
-    } else if y_b.abs() < EPSILON {
-        return b;
+    /// Get the state of the specified player.
+    pub fn get_player(&self, id: &Id) -> Option<PlayerState> {
+        self.players.get(id).map(|player| player.state())
     }
 
-    debug_assert!(y_a * y_b < 0.0);
-    debug_assert!(y_a < y_b);
+    /// Iterate over all players' states.
+    ///
+    /// The iteration order is unspecified.
+    pub fn iter_players(&self) -> impl Iterator<Item = (Id, PlayerState)> + '_ {
+        self.players
+            .iter()
+            .map(|player| (player.key().clone(), player.state()))
+    }
+}
 
-    let n_half = (((b - a) / EPSILON).log2().ceil() - 1.0).max(0.0) as usize;
-    let n_max = n_half + N_0;
-    let k_1 = 0.2 / (b - a);
+impl<Id> RatingSystem<Id> for EloMmr<Id>
+where
+    Id: PlayerId + Debug + Display + Serialize + DeserializeOwned,
+{
+    type Error = EloError<Id>;
 
-    let mut scaled_epsilon = EPSILON * (1u64 << n_max) as f64;
+    fn update_standings(
+        &mut self,
+        standings: Standings<Id>,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, EloError<Id>> {
+        EloMmr::update_standings(self, standings, time)
+    }
 
-    while b - a > 2.0 * EPSILON {
-        let x_half = 0.5 * (a + b);
-        let r = scaled_epsilon - 0.5 * (b - a);
-        let x_f = (y_b * a - y_a * b) / (y_b - y_a);
-        let sigma = x_half - x_f;
-        let delta = k_1 * (b - a).powi(2);
-        let x_t = if delta <= sigma.abs() {
-            x_f + delta.copysign(sigma)
-        } else {
-            x_half
-        };
-        let x_itp = if (x_t - x_half).abs() <= r {
-            x_t
-        } else {
-            x_half - r.copysign(sigma)
-        };
-        let y_itp = f(x_itp);
-        if y_itp > 0.0 {
-            b = x_itp;
-            y_b = y_itp;
-        } else if y_itp < 0.0 {
-            a = x_itp;
-            y_a = y_itp;
-        } else {
-            return x_itp;
-        }
-        scaled_epsilon *= 0.5;
+    fn get_ratings(&self) -> Vec<(Id, f64)> {
+        EloMmr::get_ratings(self)
     }
 
-    (a + b) * 0.5
+    fn get_rating_of(&self, id: &Id) -> Option<f64> {
+        EloMmr::get_rating_of(self, id)
+    }
 }
@@ -0,0 +1,85 @@
+use crate::{replay::LogEntry, EloError, EloMmr, Player, PlayerId, PlayerState};
+
+/// A performance to replay: its season, time and contest weight, and the performance before later resets.
+type Entry = (usize, u64, f64, f64);
+
+impl<Id: PlayerId> EloMmr<Id> {
+    /// Merge the player `from` into the player `into`, e.g. when one person has played on two accounts.
+    ///
+    /// The performances of both players are replayed in chronological order from the initial rating,
+    /// diffusing between them as updates would, to recompute the merged rating and uncertainty.
+    /// Soft resets either player went through are replayed in their place, with the shift they applied
+    /// taken out of the earlier performances first.
+    ///
+    /// `from` is removed from the system. Checkpoints are discarded, since rolling back a contest
+    /// would restore one of the merged players.
+    ///
+    /// Returns the merged player's state, or an error if a player is unknown or its history cannot be replayed,
+    /// i.e. it has no times or was partly folded into the prior term by [`EloMmr::with_max_history`].
+    pub fn merge_players(&mut self, into: Id, from: Id) -> Result<PlayerState, EloError<Id>> {
+        if into == from {
+            return Err(EloError::DuplicatePlayer(into));
+        }
+        // Every player goes through each soft reset since it first played, so the season a performance
+        // belongs to follows from the number of resets the player went through before and after it.
+        let seasons = self.seasons.len();
+        let history = |id: &Id| -> Result<Vec<Entry>, EloError<Id>> {
+            let player = self
+                .players
+                .get(id)
+                .ok_or_else(|| EloError::UnknownPlayer(id.clone()))?;
+            if player.timeline.len() + 1 != player.perfs.len()
+                || player.num_contests != player.timeline.len()
+                || player.resets.len() > seasons
+            {
+                return Err(EloError::IncompleteHistory(id.clone()));
+            }
+            let first_season = seasons - player.resets.len();
+            Ok(player
+                .timeline
+                .iter()
+                .zip(&player.perfs[1..])
+                .enumerate()
+                .map(|(i, (&(time, weight), &perf))| {
+                    let before = player.resets.iter().filter(|&&(n, _)| n <= i).count();
+                    let shift: f64 = player.resets[before..]
+                        .iter()
+                        .map(|&(_, shift)| shift)
+                        .sum();
+                    (first_season + before, time, weight, perf - shift)
+                })
+                .collect())
+        };
+        let mut entries = history(&into)?;
+        entries.extend(history(&from)?);
+        // Stable, so that `into` goes first among performances at the same time.
+        entries.sort_by_key(|&(season, time, _, _)| (season, time));
+
+        let mut player = Player::new(self.mu_init, self.sigma_init);
+        let mut season = entries.first().map_or(seasons, |&(season, _, _, _)| season);
+        for (next_season, time, weight, perf) in entries {
+            for reset in &self.seasons[season..next_season] {
+                player.soft_reset(self.mu_init, self.rho, reset.fraction, reset.sigma_increase);
+            }
+            season = next_season;
+            self.diffuse_until(&mut player, time);
+            player.add_performance(self, weight, perf)?;
+        }
+        for reset in &self.seasons[season..] {
+            player.soft_reset(self.mu_init, self.rho, reset.fraction, reset.sigma_increase);
+        }
+        if !player.mu.is_finite() || !player.sigma.is_finite() {
+            return Err(EloError::NonFinite(into));
+        }
+
+        let state = player.state();
+        self.players.insert(into.clone(), player);
+        self.players.remove(&from);
+        self.checkpoints.clear();
+        if let Some(ref mut log) = self.contest_log {
+            log.push(LogEntry::Merge { into, from });
+        }
+
+        Ok(state)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{EloMmr, PlayerId, COEFF};
+
+impl<Id: PlayerId> EloMmr<Id> {
+    /// The current rating of the player and the noise of their performance, `(μ, sqrt(σ² + β²))`.
+    ///
+    /// Unknown players get the initial rating.
+    fn performance_prior(&self, id: &Id) -> (f64, f64) {
+        let (mu, sigma) = self
+            .players
+            .get(id)
+            .map_or((self.mu_init, self.sigma_init), |player| {
+                (player.mu, player.sigma)
+            });
+        (mu, sigma.hypot(self.beta))
+    }
+
+    /// Get the probability that player `a` outperforms player `b` in their next contest.
+    ///
+    /// Both performances follow the logistic model used by updates. Their difference is approximated by
+    /// a logistic distribution of deviation `sqrt(δ_a² + δ_b²)`, so that the probability is
+    /// `(1 + tanh(COEFF (μ_a - μ_b) / 2 sqrt(δ_a² + δ_b²))) / 2`.
+    ///
+    /// Unknown players are treated as new players with the initial rating.
+    pub fn win_probability(&self, a: &Id, b: &Id) -> f64 {
+        let (mu_a, delta_a) = self.performance_prior(a);
+        let (mu_b, delta_b) = self.performance_prior(b);
+        0.5 * (1.0 + (COEFF * (mu_a - mu_b) / (2.0 * delta_a.hypot(delta_b))).tanh())
+    }
+
+    /// Get the distribution of the rank of the player among the given lineup.
+    ///
+    /// The lineup should list distinct players, and may or may not include the player itself.
+    /// The `k`-th entry of the returned Vec is the probability of finishing at rank `k + 1`,
+    /// assuming each opponent outperforms the player independently with [`EloMmr::win_probability`].
+    pub fn rank_distribution(&self, id: &Id, lineup: &[Id]) -> Vec<f64> {
+        let mut distribution = vec![1.0];
+        for opponent in lineup.iter().filter(|opponent| *opponent != id) {
+            let p = self.win_probability(opponent, id);
+            distribution.push(0.0);
+            for k in (0..distribution.len()).rev() {
+                let beaten = if k > 0 { distribution[k - 1] * p } else { 0.0 };
+                distribution[k] = distribution[k] * (1.0 - p) + beaten;
+            }
+        }
+        distribution
+    }
+
+    /// Get the expected rank of the player among the given lineup, i.e. the mean of [`EloMmr::rank_distribution`].
+    pub fn expected_rank(&self, id: &Id, lineup: &[Id]) -> f64 {
+        1.0 + lineup
+            .iter()
+            .filter(|opponent| *opponent != id)
+            .map(|opponent| self.win_probability(opponent, id))
+            .sum::<f64>()
+    }
+}
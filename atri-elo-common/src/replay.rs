@@ -0,0 +1,195 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{snapshot::PlayerV1, ContestRecord, EloError, EloMmr, PlayerId, SeasonSnapshot};
+
+/// An operation that changed the players, in the order it was applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum LogEntry<Id> {
+    Contest(ContestRecord<Id>),
+    SoftReset {
+        time: u64,
+        fraction: f64,
+        sigma_increase: f64,
+    },
+    Merge {
+        into: Id,
+        from: Id,
+    },
+    /// The state of a system that already had players when the log was enabled, always the first entry.
+    Base {
+        players: Vec<(Id, PlayerV1)>,
+        seasons: Vec<SeasonSnapshot<Id>>,
+        contests_applied: u64,
+    },
+}
+
+impl<Id: PlayerId> LogEntry<Id> {
+    fn involves(&self, id: &Id) -> bool {
+        match self {
+            LogEntry::Contest(contest) => contest.involves(id),
+            LogEntry::SoftReset { .. } | LogEntry::Base { .. } => false,
+            LogEntry::Merge { into, from } => into == id || from == id,
+        }
+    }
+
+    /// The entry as if the player had never existed, or `None` if it must be dropped.
+    fn without_player(&self, id: &Id) -> Option<LogEntry<Id>> {
+        match self {
+            LogEntry::Contest(contest) if contest.involves(id) => {
+                let mut contest = contest.clone();
+                contest.remove_player(id);
+                Some(LogEntry::Contest(contest))
+            }
+            LogEntry::Merge { .. } if self.involves(id) => None,
+            _ => Some(self.clone()),
+        }
+    }
+}
+
+/// A contest replayed by [`EloMmr::remove_player`], with its corrected results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replayed<Id = u64> {
+    /// The contest as it was applied again.
+    pub contest: ContestRecord<Id>,
+    /// The participants' performance and rating in `(player_id, perf, rating)` order.
+    ///
+    /// Empty if fewer than two participants were left, in which case the contest was dropped.
+    pub result: Vec<(Id, f64, f64)>,
+    /// The participants' display ratings right after the contest, in the order of `result`.
+    pub display_ratings: Vec<f64>,
+}
+
+impl<Id: PlayerId> EloMmr<Id> {
+    /// Log every contest, season reset and merge, so that they can be replayed by [`EloMmr::remove_player`].
+    ///
+    /// If the system already has players, their states are kept as the base state that replays start from.
+    /// Their earlier contests cannot be replayed though, so they cannot be removed, and checkpoints taken
+    /// before are discarded. The log grows with every contest. It is disabled by default.
+    pub fn with_contest_log(mut self) -> EloMmr<Id> {
+        if self.contest_log.is_none() {
+            let mut log = Vec::new();
+            if !self.players.is_empty() || !self.seasons.is_empty() || self.contests_applied > 0 {
+                log.push(LogEntry::Base {
+                    players: self
+                        .players
+                        .iter()
+                        .map(|player| (player.key().clone(), PlayerV1::from(player.value())))
+                        .collect(),
+                    seasons: self.seasons.clone(),
+                    contests_applied: self.contests_applied,
+                });
+                self.checkpoints.clear();
+            }
+            self.contest_log = Some(log);
+        }
+        self
+    }
+
+    /// Remove the player from every contest they took part in, and replay the log from the earliest one.
+    ///
+    /// The earliest contest is reached by rolling back if enough checkpoints are kept, or by replaying the log
+    /// from its base state otherwise. Contests left with fewer than two participants are dropped, and merges
+    /// involving the player are undone.
+    ///
+    /// Returns the corrected results of every contest from the earliest affected one on, or an error if the log
+    /// is disabled, the player is not in it, was rated before it was enabled or a replay fails, in which case
+    /// nothing is changed.
+    pub fn remove_player(&mut self, id: Id) -> Result<Vec<Replayed<Id>>, EloError<Id>> {
+        let log = self
+            .contest_log
+            .as_ref()
+            .ok_or(EloError::MissingContestLog)?;
+        if let Some(LogEntry::Base { players, .. }) = log.first() {
+            if players.iter().any(|(player, _)| *player == id) {
+                return Err(EloError::IncompleteHistory(id));
+            }
+        }
+        let first = log
+            .iter()
+            .position(|entry| entry.involves(&id))
+            .ok_or(EloError::UnknownPlayer(id.clone()))?;
+        let corrected: Vec<_> = log[first..]
+            .iter()
+            .filter_map(|entry| entry.without_player(&id))
+            .collect();
+
+        let mut system = self.clone();
+        let prefix = if log.len() - first <= system.checkpoints.len() {
+            for _ in first..log.len() {
+                system.rollback();
+            }
+            Vec::new()
+        } else {
+            system.players = DashMap::new();
+            system.checkpoints.clear();
+            system.seasons.clear();
+            system.contest_log = Some(Vec::new());
+            system.contests_applied = 0;
+            log[..first].to_vec()
+        };
+
+        for entry in prefix {
+            system.replay(entry)?;
+        }
+        let mut replayed = Vec::new();
+        for entry in corrected {
+            replayed.extend(system.replay(entry)?);
+        }
+        system.players.remove(&id);
+
+        *self = system;
+        Ok(replayed)
+    }
+
+    /// Apply the logged entry again, returning the results if it is a contest.
+    fn replay(&mut self, entry: LogEntry<Id>) -> Result<Option<Replayed<Id>>, EloError<Id>> {
+        match entry {
+            LogEntry::Base {
+                ref players,
+                ref seasons,
+                contests_applied,
+            } => {
+                self.players = players
+                    .iter()
+                    .map(|(id, player)| (id.clone(), player.clone().into()))
+                    .collect();
+                self.seasons = seasons.clone();
+                self.contests_applied = contests_applied;
+                if let Some(ref mut log) = self.contest_log {
+                    log.push(entry);
+                }
+                Ok(None)
+            }
+            LogEntry::Contest(contest) => {
+                let (result, display_ratings) = if contest.len() < 2 {
+                    (Vec::new(), Vec::new())
+                } else {
+                    let result = self.apply(contest.clone())?;
+                    let display_ratings = result
+                        .iter()
+                        .filter_map(|(id, _, _)| self.get_display_rating_of(id))
+                        .collect();
+                    (result, display_ratings)
+                };
+                Ok(Some(Replayed {
+                    contest,
+                    result,
+                    display_ratings,
+                }))
+            }
+            LogEntry::SoftReset {
+                time,
+                fraction,
+                sigma_increase,
+            } => {
+                self.soft_reset(time, fraction, sigma_increase)?;
+                Ok(None)
+            }
+            LogEntry::Merge { into, from } => {
+                self.merge_players(into, from)?;
+                Ok(None)
+            }
+        }
+    }
+}
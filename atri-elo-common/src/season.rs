@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{replay::LogEntry, EloError, EloMmr, Player, PlayerId};
+
+/// The final standings of a season, archived by [`EloMmr::soft_reset`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonSnapshot<Id = u64> {
+    /// The time the season ended in seconds.
+    pub time: u64,
+    /// The display ratings of all visible players at the end of the season, from best to worst.
+    pub ratings: Vec<(Id, f64)>,
+    /// How far each rating was pulled toward μ_init by the reset.
+    pub fraction: f64,
+    /// The noise diffused into each player's σ by the reset.
+    pub sigma_increase: f64,
+}
+
+impl Player {
+    /// Pull the rating toward `mu_init` by `fraction` and diffuse `sigma_increase` of noise into σ.
+    ///
+    /// The whole performance history is shifted with the rating, and the shift is recorded
+    /// so that [`EloMmr::merge_players`] can recover the original performances.
+    pub(crate) fn soft_reset(
+        &mut self,
+        mu_init: f64,
+        rho: f64,
+        fraction: f64,
+        sigma_increase: f64,
+    ) {
+        let shift = fraction * (mu_init - self.mu);
+        self.mu += shift;
+        for perf in self.perfs.iter_mut() {
+            *perf += shift;
+        }
+        self.diffuse(rho, sigma_increase);
+        self.resets.push((self.num_contests, shift));
+    }
+}
+
+impl<Id: PlayerId> EloMmr<Id> {
+    /// End the season at `time`, archiving its final standings and softly resetting every player.
+    ///
+    /// Each player's rating is pulled toward μ_init by `fraction` (0 keeps it, 1 resets it fully),
+    /// and their σ is raised as if `sigma_increase` of noise had been diffused.
+    /// The whole performance history is shifted with the rating, so that later updates keep the reset.
+    ///
+    /// Checkpoints are discarded, since rolling back an earlier contest would undo the reset.
+    pub fn soft_reset(
+        &mut self,
+        time: u64,
+        fraction: f64,
+        sigma_increase: f64,
+    ) -> Result<(), EloError<Id>> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(EloError::InvalidParameter("fraction"));
+        }
+        if !sigma_increase.is_finite() || sigma_increase < 0.0 {
+            return Err(EloError::InvalidParameter("sigma_increase"));
+        }
+
+        let mut ratings = self.get_display_ratings();
+        ratings.sort_unstable_by(|a, b| b.1.total_cmp(&a.1));
+        self.seasons.push(SeasonSnapshot {
+            time,
+            ratings,
+            fraction,
+            sigma_increase,
+        });
+
+        for mut player in self.players.iter_mut() {
+            player.soft_reset(self.mu_init, self.rho, fraction, sigma_increase);
+        }
+        self.checkpoints.clear();
+        if let Some(ref mut log) = self.contest_log {
+            log.push(LogEntry::SoftReset {
+                time,
+                fraction,
+                sigma_increase,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Get the archived snapshots of past seasons, from oldest to newest.
+    pub fn seasons(&self) -> &[SeasonSnapshot<Id>] {
+        &self.seasons
+    }
+}
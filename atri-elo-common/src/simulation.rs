@@ -0,0 +1,199 @@
+use crate::{topcoder::inverse_normal_cdf, RatingSystem};
+
+/// A simulation of contests between synthetic players with known true skills.
+///
+/// Each contest draws a random lineup, and every participant performs at their true skill plus gaussian noise.
+/// The results are fed to a rating system to measure how fast and how well it recovers the true skills.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    num_players: usize,
+    num_contests: usize,
+    players_per_contest: usize,
+    skill_mean: f64,
+    skill_deviation: f64,
+    noise: f64,
+    seed: u64,
+}
+
+/// The outcome of a [`Simulation`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    /// The true skill of every player in `(player_id, skill)` order.
+    pub skills: Vec<(u64, f64)>,
+    /// The Spearman rank correlation between the ratings and the true skills of rated players after each contest.
+    pub rank_correlation: Vec<f64>,
+    /// The root mean square difference between the ratings and the true skills of rated players after each contest.
+    pub rating_error: Vec<f64>,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new(100, 100, 20)
+    }
+}
+
+impl Simulation {
+    /// Construct a new simulation of `num_contests` contests between `players_per_contest` of `num_players` players.
+    ///
+    /// Skills follow the prior of [`crate::EloMmr::default`] (mean 1500, deviation 350), and performances have noise 200.
+    pub fn new(num_players: usize, num_contests: usize, players_per_contest: usize) -> Simulation {
+        Simulation {
+            num_players,
+            num_contests,
+            players_per_contest: players_per_contest.min(num_players),
+            skill_mean: 1500.0,
+            skill_deviation: 350.0,
+            noise: 200.0,
+            seed: 0,
+        }
+    }
+
+    /// Set the mean and the deviation of the true skills.
+    pub fn with_skills(mut self, mean: f64, deviation: f64) -> Simulation {
+        self.skill_mean = mean;
+        self.skill_deviation = deviation;
+        self
+    }
+
+    /// Set the deviation of the noise added to a player's true skill in each contest.
+    pub fn with_noise(mut self, noise: f64) -> Simulation {
+        self.noise = noise;
+        self
+    }
+
+    /// Set the seed of the random generator, so that different runs can be compared on the same contests.
+    pub fn with_seed(mut self, seed: u64) -> Simulation {
+        self.seed = seed;
+        self
+    }
+
+    /// Run the simulation through the given system, with players numbered from 0 and contest `i` held at time `i`.
+    ///
+    /// Fails if the system fails to process a contest.
+    pub fn run<S: RatingSystem>(&self, system: &mut S) -> Result<SimulationReport, S::Error> {
+        let mut rng = SplitMix64(self.seed);
+        let skills: Vec<f64> = (0..self.num_players)
+            .map(|_| self.skill_mean + self.skill_deviation * rng.normal())
+            .collect();
+
+        let mut ids: Vec<u64> = (0..self.num_players as u64).collect();
+        let mut rank_correlation = Vec::with_capacity(self.num_contests);
+        let mut rating_error = Vec::with_capacity(self.num_contests);
+        for time in 0..self.num_contests {
+            // A partial Fisher-Yates shuffle puts a uniform lineup in front.
+            for i in 0..self.players_per_contest {
+                let j = i + (rng.next_u64() % (ids.len() - i) as u64) as usize;
+                ids.swap(i, j);
+            }
+            let contest_scores = ids[..self.players_per_contest]
+                .iter()
+                .map(|&id| (id, skills[id as usize] + self.noise * rng.normal()))
+                .collect();
+            system.update(contest_scores, time as u64)?;
+
+            let (ratings, truth): (Vec<f64>, Vec<f64>) = (0..self.num_players as u64)
+                .filter_map(|id| {
+                    system
+                        .get_rating_of(&id)
+                        .map(|rating| (rating, skills[id as usize]))
+                })
+                .unzip();
+            rank_correlation.push(spearman(&ratings, &truth));
+            rating_error.push(
+                (ratings
+                    .iter()
+                    .zip(&truth)
+                    .map(|(rating, skill)| (rating - skill).powi(2))
+                    .sum::<f64>()
+                    / ratings.len().max(1) as f64)
+                    .sqrt(),
+            );
+        }
+
+        Ok(SimulationReport {
+            skills: skills
+                .into_iter()
+                .enumerate()
+                .map(|(id, skill)| (id as u64, skill))
+                .collect(),
+            rank_correlation,
+            rating_error,
+        })
+    }
+}
+
+impl SimulationReport {
+    /// Get the number of contests after which the rank correlation stays at or above `threshold`.
+    ///
+    /// Returns None if the correlation is below `threshold` after the last contest.
+    pub fn contests_to_converge(&self, threshold: f64) -> Option<usize> {
+        let below = self
+            .rank_correlation
+            .iter()
+            .rposition(|&correlation| correlation < threshold);
+        match below {
+            Some(index) if index + 1 == self.rank_correlation.len() => None,
+            Some(index) => Some(index + 2),
+            None if self.rank_correlation.is_empty() => None,
+            None => Some(1),
+        }
+    }
+}
+
+/// The Spearman rank correlation of two samples, i.e. the Pearson correlation of their ranks.
+///
+/// Tied values are given the average rank of their tie. Returns 0 if either sample is constant.
+pub(crate) fn spearman(xs: &[f64], ys: &[f64]) -> f64 {
+    let (xs, ys) = (fractional_ranks(xs), fractional_ranks(ys));
+    let n = xs.len() as f64;
+    let mean = (n + 1.0) / 2.0;
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(&ys) {
+        cov += (x - mean) * (y - mean);
+        var_x += (x - mean).powi(2);
+        var_y += (y - mean).powi(2);
+    }
+    if var_x == 0.0 || var_y == 0.0 {
+        0.0
+    } else {
+        cov / (var_x * var_y).sqrt()
+    }
+}
+
+/// The 1-based ranks of the values, where tied values share the average rank of their tie.
+fn fractional_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_unstable_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let len = order[start..]
+            .iter()
+            .take_while(|&&i| values[i] == values[order[start]])
+            .count();
+        for &i in &order[start..start + len] {
+            ranks[i] = start as f64 + (len + 1) as f64 / 2.0;
+        }
+        start += len;
+    }
+    ranks
+}
+
+/// The SplitMix64 generator, which is small and good enough for simulations.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A standard normal sample, by inverting the CDF at a uniform point of (0, 1).
+    fn normal(&mut self) -> f64 {
+        let uniform = ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+        inverse_normal_cdf(uniform)
+    }
+}
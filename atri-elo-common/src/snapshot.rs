@@ -0,0 +1,778 @@
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use dashmap::DashMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    replay::LogEntry, Checkpoint, ContestRecord, EloError, EloMmr, InactivityPolicy,
+    PerformanceModel, Player, PlayerId, SeasonSnapshot, SolverConfig, SolverMethod,
+    TeamAggregation,
+};
+
+/// The version of the snapshot format written by [`EloMmr::to_json`] and [`EloMmr::to_binary`].
+///
+/// Version 0 is the original serialization of [`EloMmr`], before snapshots had a header.
+///
+/// Snapshots are written through private copies of the types they hold, so that changes to the types
+/// of the crate do not change the format. Any change to those copies needs a new version.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The magic bytes that start a binary snapshot.
+const MAGIC: &[u8; 4] = b"AELO";
+
+/// The hyperparameters of a system, as recorded in a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hyperparameters {
+    pub rho: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub mu_init: f64,
+    pub sigma_init: f64,
+    pub drift_per_sec: f64,
+    pub model: PerformanceModel,
+    pub max_history: Option<usize>,
+    pub approximation: Option<(usize, f64)>,
+    pub solver: SolverConfig,
+    pub team_aggregation: TeamAggregation,
+    pub display_sigma_factor: f64,
+    pub provisional: Option<(f64, usize)>,
+    pub inactivity: Option<InactivityPolicy>,
+    pub checkpoint_limit: usize,
+}
+
+/// The header of a snapshot, describing what produced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    /// The version of the snapshot format.
+    pub version: u32,
+    /// The hyperparameters of the system.
+    pub hyperparameters: Hyperparameters,
+    /// The number of contests applied to the system, see [`EloMmr::contests_applied`].
+    pub contests_applied: u64,
+}
+
+/// An error returned when a snapshot cannot be written or read.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The JSON encoding failed.
+    Json(serde_json::Error),
+    /// The binary encoding failed.
+    Binary(bincode::Error),
+    /// The binary data does not start with the snapshot magic bytes.
+    NotASnapshot,
+    /// The snapshot was written by a newer version of the format.
+    UnsupportedVersion(u32),
+    /// The snapshot holds invalid hyperparameters.
+    Invalid(EloError),
+    /// A player of a version 0 snapshot has no prior term, or more performances than weights.
+    InvalidPlayer,
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Json(err) => write!(f, "invalid JSON snapshot: {}", err),
+            SnapshotError::Binary(err) => write!(f, "invalid binary snapshot: {}", err),
+            SnapshotError::NotASnapshot => write!(f, "data is not a binary snapshot"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {}", version)
+            }
+            SnapshotError::Invalid(err) => write!(f, "invalid snapshot: {}", err),
+            SnapshotError::InvalidPlayer => write!(f, "snapshot holds an inconsistent player"),
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SnapshotError::Json(err) => Some(err),
+            SnapshotError::Binary(err) => Some(err),
+            SnapshotError::Invalid(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        SnapshotError::Json(err)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(err: bincode::Error) -> Self {
+        SnapshotError::Binary(err)
+    }
+}
+
+/// The state of a player in snapshots, and in the base state of the contest log.
+///
+/// Kept apart from `Player`, so that changes to the latter do not change the format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PlayerV1 {
+    mu: f64,
+    mu_pi: f64,
+    sigma: f64,
+    delta: f64,
+    perfs: Vec<f64>,
+    weights: Vec<f64>,
+    last_time: Option<u64>,
+    num_contests: usize,
+    timeline: Vec<(u64, f64)>,
+    resets: Vec<(usize, f64)>,
+}
+
+impl From<&Player> for PlayerV1 {
+    fn from(player: &Player) -> Self {
+        PlayerV1 {
+            mu: player.mu,
+            mu_pi: player.mu_pi,
+            sigma: player.sigma,
+            delta: player.delta,
+            perfs: player.perfs.clone(),
+            weights: player.weights.clone(),
+            last_time: player.last_time,
+            num_contests: player.num_contests,
+            timeline: player.timeline.clone(),
+            resets: player.resets.clone(),
+        }
+    }
+}
+
+impl From<PlayerV1> for Player {
+    fn from(player: PlayerV1) -> Self {
+        Player {
+            mu: player.mu,
+            mu_pi: player.mu_pi,
+            sigma: player.sigma,
+            delta: player.delta,
+            perfs: player.perfs,
+            weights: player.weights,
+            last_time: player.last_time,
+            num_contests: player.num_contests,
+            timeline: player.timeline,
+            resets: player.resets,
+        }
+    }
+}
+
+/// A player in version 0, the original serialization of [`EloMmr`].
+#[derive(Debug, Clone, Deserialize)]
+struct PlayerV0 {
+    mu: f64,
+    mu_pi: f64,
+    sigma: f64,
+    delta: f64,
+    perfs: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl From<PlayerV0> for Player {
+    fn from(player: PlayerV0) -> Self {
+        Player {
+            mu: player.mu,
+            mu_pi: player.mu_pi,
+            sigma: player.sigma,
+            delta: player.delta,
+            num_contests: player.perfs.len() - 1,
+            perfs: player.perfs,
+            weights: player.weights,
+            last_time: None,
+            timeline: Vec::new(),
+            resets: Vec::new(),
+        }
+    }
+}
+
+/// A version 0 snapshot, the original serialization of [`EloMmr`] before snapshots had a header.
+#[derive(Debug, Clone, Deserialize)]
+struct SystemV0<Id: PlayerId> {
+    rho: f64,
+    beta: f64,
+    gamma: f64,
+    mu_init: f64,
+    sigma_init: f64,
+    players: HashMap<Id, PlayerV0>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum PerformanceModelV1 {
+    Logistic,
+    Gaussian,
+}
+
+impl From<PerformanceModel> for PerformanceModelV1 {
+    fn from(model: PerformanceModel) -> Self {
+        match model {
+            PerformanceModel::Logistic => PerformanceModelV1::Logistic,
+            PerformanceModel::Gaussian => PerformanceModelV1::Gaussian,
+        }
+    }
+}
+
+impl From<PerformanceModelV1> for PerformanceModel {
+    fn from(model: PerformanceModelV1) -> Self {
+        match model {
+            PerformanceModelV1::Logistic => PerformanceModel::Logistic,
+            PerformanceModelV1::Gaussian => PerformanceModel::Gaussian,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum TeamAggregationV1 {
+    Mean,
+    Sum,
+}
+
+impl From<TeamAggregation> for TeamAggregationV1 {
+    fn from(aggregation: TeamAggregation) -> Self {
+        match aggregation {
+            TeamAggregation::Mean => TeamAggregationV1::Mean,
+            TeamAggregation::Sum => TeamAggregationV1::Sum,
+        }
+    }
+}
+
+impl From<TeamAggregationV1> for TeamAggregation {
+    fn from(aggregation: TeamAggregationV1) -> Self {
+        match aggregation {
+            TeamAggregationV1::Mean => TeamAggregation::Mean,
+            TeamAggregationV1::Sum => TeamAggregation::Sum,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum SolverMethodV1 {
+    Itp,
+    Newton,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct SolverConfigV1 {
+    method: SolverMethodV1,
+    tolerance: f64,
+    max_iterations: usize,
+}
+
+impl From<SolverConfig> for SolverConfigV1 {
+    fn from(config: SolverConfig) -> Self {
+        SolverConfigV1 {
+            method: match config.method {
+                SolverMethod::Itp => SolverMethodV1::Itp,
+                SolverMethod::Newton => SolverMethodV1::Newton,
+            },
+            tolerance: config.tolerance,
+            max_iterations: config.max_iterations,
+        }
+    }
+}
+
+impl From<SolverConfigV1> for SolverConfig {
+    fn from(config: SolverConfigV1) -> Self {
+        SolverConfig {
+            method: match config.method {
+                SolverMethodV1::Itp => SolverMethod::Itp,
+                SolverMethodV1::Newton => SolverMethod::Newton,
+            },
+            tolerance: config.tolerance,
+            max_iterations: config.max_iterations,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum InactivityPolicyV1 {
+    IncreaseSigma {
+        grace_secs: u64,
+        variance_per_sec: f64,
+    },
+    Hide {
+        after_secs: u64,
+    },
+}
+
+impl From<InactivityPolicy> for InactivityPolicyV1 {
+    fn from(policy: InactivityPolicy) -> Self {
+        match policy {
+            InactivityPolicy::IncreaseSigma {
+                grace_secs,
+                variance_per_sec,
+            } => InactivityPolicyV1::IncreaseSigma {
+                grace_secs,
+                variance_per_sec,
+            },
+            InactivityPolicy::Hide { after_secs } => InactivityPolicyV1::Hide { after_secs },
+        }
+    }
+}
+
+impl From<InactivityPolicyV1> for InactivityPolicy {
+    fn from(policy: InactivityPolicyV1) -> Self {
+        match policy {
+            InactivityPolicyV1::IncreaseSigma {
+                grace_secs,
+                variance_per_sec,
+            } => InactivityPolicy::IncreaseSigma {
+                grace_secs,
+                variance_per_sec,
+            },
+            InactivityPolicyV1::Hide { after_secs } => InactivityPolicy::Hide { after_secs },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HyperparametersV1 {
+    rho: f64,
+    beta: f64,
+    gamma: f64,
+    mu_init: f64,
+    sigma_init: f64,
+    drift_per_sec: f64,
+    model: PerformanceModelV1,
+    max_history: Option<usize>,
+    approximation: Option<(usize, f64)>,
+    solver: SolverConfigV1,
+    team_aggregation: TeamAggregationV1,
+    display_sigma_factor: f64,
+    provisional: Option<(f64, usize)>,
+    inactivity: Option<InactivityPolicyV1>,
+    checkpoint_limit: usize,
+}
+
+impl From<Hyperparameters> for HyperparametersV1 {
+    fn from(hyperparameters: Hyperparameters) -> Self {
+        HyperparametersV1 {
+            rho: hyperparameters.rho,
+            beta: hyperparameters.beta,
+            gamma: hyperparameters.gamma,
+            mu_init: hyperparameters.mu_init,
+            sigma_init: hyperparameters.sigma_init,
+            drift_per_sec: hyperparameters.drift_per_sec,
+            model: hyperparameters.model.into(),
+            max_history: hyperparameters.max_history,
+            approximation: hyperparameters.approximation,
+            solver: hyperparameters.solver.into(),
+            team_aggregation: hyperparameters.team_aggregation.into(),
+            display_sigma_factor: hyperparameters.display_sigma_factor,
+            provisional: hyperparameters.provisional,
+            inactivity: hyperparameters.inactivity.map(InactivityPolicyV1::from),
+            checkpoint_limit: hyperparameters.checkpoint_limit,
+        }
+    }
+}
+
+impl From<HyperparametersV1> for Hyperparameters {
+    fn from(hyperparameters: HyperparametersV1) -> Self {
+        Hyperparameters {
+            rho: hyperparameters.rho,
+            beta: hyperparameters.beta,
+            gamma: hyperparameters.gamma,
+            mu_init: hyperparameters.mu_init,
+            sigma_init: hyperparameters.sigma_init,
+            drift_per_sec: hyperparameters.drift_per_sec,
+            model: hyperparameters.model.into(),
+            max_history: hyperparameters.max_history,
+            approximation: hyperparameters.approximation,
+            solver: hyperparameters.solver.into(),
+            team_aggregation: hyperparameters.team_aggregation.into(),
+            display_sigma_factor: hyperparameters.display_sigma_factor,
+            provisional: hyperparameters.provisional,
+            inactivity: hyperparameters.inactivity.map(InactivityPolicy::from),
+            checkpoint_limit: hyperparameters.checkpoint_limit,
+        }
+    }
+}
+
+/// The header of a version 1 snapshot. Every version starts with the version number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotHeaderV1 {
+    version: u32,
+    hyperparameters: HyperparametersV1,
+    contests_applied: u64,
+}
+
+impl From<SnapshotHeader> for SnapshotHeaderV1 {
+    fn from(header: SnapshotHeader) -> Self {
+        SnapshotHeaderV1 {
+            version: header.version,
+            hyperparameters: header.hyperparameters.into(),
+            contests_applied: header.contests_applied,
+        }
+    }
+}
+
+impl From<SnapshotHeaderV1> for SnapshotHeader {
+    fn from(header: SnapshotHeaderV1) -> Self {
+        SnapshotHeader {
+            version: header.version,
+            hyperparameters: header.hyperparameters.into(),
+            contests_applied: header.contests_applied,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ContestRecordV1<Id> {
+    standings: Vec<(Id, u64, u64)>,
+    teams: Option<Vec<Vec<Id>>>,
+    team_standings: Vec<(u64, u64, u64)>,
+    time: u64,
+    weight: f64,
+    id: Option<u64>,
+}
+
+impl<Id: Clone> From<&ContestRecord<Id>> for ContestRecordV1<Id> {
+    fn from(contest: &ContestRecord<Id>) -> Self {
+        ContestRecordV1 {
+            standings: contest.standings.clone(),
+            teams: contest.teams.clone(),
+            team_standings: contest.team_standings.clone(),
+            time: contest.time,
+            weight: contest.weight,
+            id: contest.id,
+        }
+    }
+}
+
+impl<Id> From<ContestRecordV1<Id>> for ContestRecord<Id> {
+    fn from(contest: ContestRecordV1<Id>) -> Self {
+        ContestRecord {
+            standings: contest.standings,
+            teams: contest.teams,
+            team_standings: contest.team_standings,
+            time: contest.time,
+            weight: contest.weight,
+            id: contest.id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SeasonSnapshotV1<Id> {
+    time: u64,
+    ratings: Vec<(Id, f64)>,
+    fraction: f64,
+    sigma_increase: f64,
+}
+
+impl<Id: Clone> From<&SeasonSnapshot<Id>> for SeasonSnapshotV1<Id> {
+    fn from(season: &SeasonSnapshot<Id>) -> Self {
+        SeasonSnapshotV1 {
+            time: season.time,
+            ratings: season.ratings.clone(),
+            fraction: season.fraction,
+            sigma_increase: season.sigma_increase,
+        }
+    }
+}
+
+impl<Id> From<SeasonSnapshotV1<Id>> for SeasonSnapshot<Id> {
+    fn from(season: SeasonSnapshotV1<Id>) -> Self {
+        SeasonSnapshot {
+            time: season.time,
+            ratings: season.ratings,
+            fraction: season.fraction,
+            sigma_increase: season.sigma_increase,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum LogEntryV1<Id> {
+    Contest(ContestRecordV1<Id>),
+    SoftReset {
+        time: u64,
+        fraction: f64,
+        sigma_increase: f64,
+    },
+    Merge {
+        into: Id,
+        from: Id,
+    },
+    Base {
+        players: Vec<(Id, PlayerV1)>,
+        seasons: Vec<SeasonSnapshotV1<Id>>,
+        contests_applied: u64,
+    },
+}
+
+impl<Id: Clone> From<&LogEntry<Id>> for LogEntryV1<Id> {
+    fn from(entry: &LogEntry<Id>) -> Self {
+        match entry {
+            LogEntry::Contest(contest) => LogEntryV1::Contest(contest.into()),
+            &LogEntry::SoftReset {
+                time,
+                fraction,
+                sigma_increase,
+            } => LogEntryV1::SoftReset {
+                time,
+                fraction,
+                sigma_increase,
+            },
+            LogEntry::Merge { into, from } => LogEntryV1::Merge {
+                into: into.clone(),
+                from: from.clone(),
+            },
+            LogEntry::Base {
+                players,
+                seasons,
+                contests_applied,
+            } => LogEntryV1::Base {
+                players: players.clone(),
+                seasons: seasons.iter().map(SeasonSnapshotV1::from).collect(),
+                contests_applied: *contests_applied,
+            },
+        }
+    }
+}
+
+impl<Id> From<LogEntryV1<Id>> for LogEntry<Id> {
+    fn from(entry: LogEntryV1<Id>) -> Self {
+        match entry {
+            LogEntryV1::Contest(contest) => LogEntry::Contest(contest.into()),
+            LogEntryV1::SoftReset {
+                time,
+                fraction,
+                sigma_increase,
+            } => LogEntry::SoftReset {
+                time,
+                fraction,
+                sigma_increase,
+            },
+            LogEntryV1::Merge { into, from } => LogEntry::Merge { into, from },
+            LogEntryV1::Base {
+                players,
+                seasons,
+                contests_applied,
+            } => LogEntry::Base {
+                players,
+                seasons: seasons.into_iter().map(SeasonSnapshot::from).collect(),
+                contests_applied,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CheckpointV1<Id> {
+    contest: ContestRecordV1<Id>,
+    players: Vec<(Id, Option<PlayerV1>)>,
+}
+
+/// A version 1 snapshot. Players are sorted by id, so that equal systems give equal snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SnapshotV1<Id> {
+    header: SnapshotHeaderV1,
+    now: Option<u64>,
+    seasons: Vec<SeasonSnapshotV1<Id>>,
+    contest_log: Option<Vec<LogEntryV1<Id>>>,
+    players: Vec<(Id, PlayerV1)>,
+    checkpoints: Vec<CheckpointV1<Id>>,
+}
+
+impl<Id: PlayerId + Ord + Serialize + DeserializeOwned> EloMmr<Id> {
+    /// Get the header a snapshot of the system would have.
+    pub fn snapshot_header(&self) -> SnapshotHeader {
+        SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            hyperparameters: Hyperparameters {
+                rho: self.rho,
+                beta: self.beta,
+                gamma: self.gamma,
+                mu_init: self.mu_init,
+                sigma_init: self.sigma_init,
+                drift_per_sec: self.drift_per_sec,
+                model: self.model,
+                max_history: self.max_history,
+                approximation: self.approximation,
+                solver: self.solver,
+                team_aggregation: self.team_aggregation,
+                display_sigma_factor: self.display_sigma_factor,
+                provisional: self.provisional,
+                inactivity: self.inactivity,
+                checkpoint_limit: self.checkpoint_limit,
+            },
+            contests_applied: self.contests_applied,
+        }
+    }
+
+    /// Write a snapshot of the system as JSON.
+    pub fn to_json(&self) -> Result<String, SnapshotError> {
+        Ok(serde_json::to_string(&self.snapshot())?)
+    }
+
+    /// Read a JSON snapshot of any version, migrating it to the current one.
+    ///
+    /// JSON without a header is read as version 0, which only holds the superparameters and the players.
+    pub fn from_json(json: &str) -> Result<EloMmr<Id>, SnapshotError> {
+        let value: Value = serde_json::from_str(json)?;
+        let version = match value.get("header") {
+            Some(header) => u32::deserialize(&header["version"])?,
+            None => 0,
+        };
+
+        match version {
+            0 => EloMmr::from_legacy(serde_json::from_value(value)?),
+            1 => EloMmr::from_snapshot(serde_json::from_value(value)?),
+            version => Err(SnapshotError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Write a compact binary snapshot of the system.
+    pub fn to_binary(&self) -> Result<Vec<u8>, SnapshotError> {
+        let mut buf = MAGIC.to_vec();
+        bincode::serialize_into(&mut buf, &self.snapshot())?;
+        Ok(buf)
+    }
+
+    /// Read a binary snapshot.
+    ///
+    /// Fails with [`SnapshotError::NotASnapshot`] if the data was not written by [`EloMmr::to_binary`].
+    pub fn from_binary(buf: &[u8]) -> Result<EloMmr<Id>, SnapshotError> {
+        let body = buf
+            .strip_prefix(MAGIC.as_slice())
+            .ok_or(SnapshotError::NotASnapshot)?;
+        // Every version starts with the header, which starts with the version.
+        let version: u32 = bincode::deserialize(body)?;
+
+        match version {
+            1 => EloMmr::from_snapshot(bincode::deserialize(body)?),
+            version => Err(SnapshotError::UnsupportedVersion(version)),
+        }
+    }
+
+    fn snapshot(&self) -> SnapshotV1<Id> {
+        let mut players: Vec<_> = self
+            .players
+            .iter()
+            .map(|player| (player.key().clone(), PlayerV1::from(player.value())))
+            .collect();
+        players.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        let checkpoints = self
+            .checkpoints
+            .iter()
+            .map(|checkpoint| CheckpointV1 {
+                contest: (&checkpoint.contest).into(),
+                players: checkpoint
+                    .players
+                    .iter()
+                    .map(|(id, player)| (id.clone(), player.as_ref().map(PlayerV1::from)))
+                    .collect(),
+            })
+            .collect();
+
+        SnapshotV1 {
+            header: self.snapshot_header().into(),
+            now: self.now,
+            seasons: self.seasons.iter().map(SeasonSnapshotV1::from).collect(),
+            contest_log: self
+                .contest_log
+                .as_ref()
+                .map(|log| log.iter().map(LogEntryV1::from).collect()),
+            players,
+            checkpoints,
+        }
+    }
+
+    fn from_snapshot(snapshot: SnapshotV1<Id>) -> Result<EloMmr<Id>, SnapshotError> {
+        let Hyperparameters {
+            rho,
+            beta,
+            gamma,
+            mu_init,
+            sigma_init,
+            drift_per_sec,
+            model,
+            max_history,
+            approximation,
+            solver,
+            team_aggregation,
+            display_sigma_factor,
+            provisional,
+            inactivity,
+            checkpoint_limit,
+        } = snapshot.header.hyperparameters.into();
+
+        let system = EloMmr {
+            drift_per_sec,
+            model,
+            max_history,
+            approximation,
+            solver,
+            team_aggregation,
+            display_sigma_factor,
+            provisional,
+            inactivity,
+            now: snapshot.now,
+            seasons: snapshot
+                .seasons
+                .into_iter()
+                .map(SeasonSnapshot::from)
+                .collect(),
+            contest_log: snapshot
+                .contest_log
+                .map(|log| log.into_iter().map(LogEntry::from).collect()),
+            contests_applied: snapshot.header.contests_applied,
+            players: snapshot
+                .players
+                .into_iter()
+                .map(|(id, player)| (id, player.into()))
+                .collect::<DashMap<_, _>>(),
+            checkpoint_limit,
+            checkpoints: snapshot
+                .checkpoints
+                .into_iter()
+                .map(|checkpoint| Checkpoint {
+                    contest: checkpoint.contest.into(),
+                    players: checkpoint
+                        .players
+                        .into_iter()
+                        .map(|(id, player)| (id, player.map(Player::from)))
+                        .collect(),
+                })
+                .collect(),
+            ..EloMmr::new(rho, beta, gamma, mu_init, sigma_init)
+        };
+        system.check_snapshot()?;
+
+        Ok(system)
+    }
+
+    fn from_legacy(legacy: SystemV0<Id>) -> Result<EloMmr<Id>, SnapshotError> {
+        if legacy
+            .players
+            .values()
+            .any(|player| player.perfs.is_empty() || player.perfs.len() != player.weights.len())
+        {
+            return Err(SnapshotError::InvalidPlayer);
+        }
+        let system = EloMmr {
+            players: legacy
+                .players
+                .into_iter()
+                .map(|(id, player)| (id, player.into()))
+                .collect::<DashMap<_, _>>(),
+            ..EloMmr::new(
+                legacy.rho,
+                legacy.beta,
+                legacy.gamma,
+                legacy.mu_init,
+                legacy.sigma_init,
+            )
+        };
+        system.check_snapshot()?;
+
+        Ok(system)
+    }
+
+    fn check_snapshot(&self) -> Result<(), SnapshotError> {
+        self.check_parameters()
+            .map_err(|name| SnapshotError::Invalid(EloError::InvalidParameter(name)))
+    }
+}
@@ -0,0 +1,252 @@
+use std::{error::Error, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+/// The root-finding method used to solve performance and rating equations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverMethod {
+    /// The ITP method, a bracketing method with the worst case of bisection.
+    #[default]
+    Itp,
+    /// Newton's method using analytic derivatives, falling back to ITP when a step leaves the bracket.
+    Newton,
+}
+
+/// Configuration of the root solver.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SolverConfig {
+    /// The root-finding method.
+    pub method: SolverMethod,
+    /// The tolerance on the root.
+    pub tolerance: f64,
+    /// The maximum number of iterations before giving up.
+    pub max_iterations: usize,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            method: SolverMethod::Itp,
+            tolerance: 1e-10,
+            max_iterations: 100,
+        }
+    }
+}
+
+/// An error returned when an equation cannot be solved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolveError {
+    /// The function does not go from negative to positive over the bracket `[a, b]`.
+    Bracket { a: f64, b: f64 },
+    /// The solver did not converge within the maximum number of iterations.
+    NoConvergence,
+    /// The function evaluated to a non-finite value.
+    NonFinite,
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Bracket { a, b } => write!(f, "no root bracketed in [{}, {}]", a, b),
+            SolveError::NoConvergence => write!(f, "solver did not converge"),
+            SolveError::NonFinite => write!(f, "function evaluated to a non-finite value"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// Solve f(x) = 0 with the configured method, searching outwards from `guess`.
+///
+/// `f` returns the value and the derivative at `x`, and must be increasing.
+/// The bracket starts as `guess ± scale` and is widened until `f` changes sign over it.
+pub(crate) fn solve(
+    guess: f64,
+    scale: f64,
+    mut f: impl FnMut(f64) -> (f64, f64),
+    config: &SolverConfig,
+) -> Result<f64, SolveError> {
+    let (bound, values) = expand_bracket(guess, scale, |x| f(x).0)?;
+    match config.method {
+        SolverMethod::Itp => solve_itp(bound, values, |x| f(x).0, config),
+        SolverMethod::Newton => solve_newton(bound, values, guess, f, config),
+    }
+}
+
+/// A bracket `[a, b]` together with the values `(f(a), f(b))`.
+type Bracket = ((f64, f64), (f64, f64));
+
+/// Find `[a, b]` around `center` such that `f(a) <= 0 <= f(b)` for an increasing `f`.
+///
+/// Each side that has the wrong sign is pushed out by the current width of the bracket.
+/// The values at the ends are returned as well, so that the solvers need not evaluate them again.
+fn expand_bracket(
+    center: f64,
+    scale: f64,
+    mut f: impl FnMut(f64) -> f64,
+) -> Result<Bracket, SolveError> {
+    const MAX_EXPANSIONS: usize = 64;
+
+    let (mut a, mut b) = (center - scale, center + scale);
+    for _ in 0..MAX_EXPANSIONS {
+        let (y_a, y_b) = (f(a), f(b));
+        if !y_a.is_finite() || !y_b.is_finite() {
+            return Err(SolveError::NonFinite);
+        }
+        if y_a <= 0.0 && 0.0 <= y_b {
+            return Ok(((a, b), (y_a, y_b)));
+        }
+
+        let width = b - a;
+        if y_a > 0.0 {
+            a -= width;
+        }
+        if y_b < 0.0 {
+            b += width;
+        }
+    }
+
+    Err(SolveError::Bracket { a, b })
+}
+
+/// The configured tolerance, widened to the floating-point resolution of `[a, b]` if needed.
+fn tolerance(config: &SolverConfig, (a, b): (f64, f64)) -> f64 {
+    config
+        .tolerance
+        .max(4.0 * f64::EPSILON * a.abs().max(b.abs()))
+}
+
+/// Check that `f(a) < 0 < f(b)`, returning early if either end is already a root.
+fn check_bracket(
+    (a, b): (f64, f64),
+    (y_a, y_b): (f64, f64),
+    epsilon: f64,
+) -> Result<Option<f64>, SolveError> {
+    if !y_a.is_finite() || !y_b.is_finite() {
+        return Err(SolveError::NonFinite);
+    }
+
+    if y_a.abs() < epsilon {
+        Ok(Some(a))
+    } else if y_b.abs() < epsilon {
+        Ok(Some(b))
+    } else if a < b && y_a < 0.0 && 0.0 < y_b {
+        Ok(None)
+    } else {
+        Err(SolveError::Bracket { a, b })
+    }
+}
+
+/// Solve f(x) = 0 where x belongs to [a, b] with the ITP method, given `(f(a), f(b))`.
+///
+/// Fails when `a < b` or `f(a) < 0 < f(b)` is not satisfied.
+pub(crate) fn solve_itp(
+    (mut a, mut b): (f64, f64),
+    (mut y_a, mut y_b): (f64, f64),
+    mut f: impl FnMut(f64) -> f64,
+    config: &SolverConfig,
+) -> Result<f64, SolveError> {
+    const N_0: i32 = 1;
+
+    let epsilon = tolerance(config, (a, b));
+
+    if let Some(root) = check_bracket((a, b), (y_a, y_b), epsilon)? {
+        return Ok(root);
+    }
+
+    let n_half = (((b - a) / epsilon).log2().ceil() - 1.0).max(0.0) as i32;
+    let n_max = n_half + N_0;
+    let k_1 = 0.2 / (b - a);
+
+    let mut scaled_epsilon = epsilon * 2f64.powi(n_max);
+
+    let mut iterations = 0;
+    while b - a > 2.0 * epsilon {
+        if iterations == config.max_iterations {
+            return Err(SolveError::NoConvergence);
+        }
+        iterations += 1;
+
+        let x_half = 0.5 * (a + b);
+        let r = scaled_epsilon - 0.5 * (b - a);
+        let x_f = (y_b * a - y_a * b) / (y_b - y_a);
+        let sigma = x_half - x_f;
+        let delta = k_1 * (b - a).powi(2);
+        let x_t = if delta <= sigma.abs() {
+            x_f + delta.copysign(sigma)
+        } else {
+            x_half
+        };
+        let x_itp = if (x_t - x_half).abs() <= r {
+            x_t
+        } else {
+            x_half - r.copysign(sigma)
+        };
+        let y_itp = f(x_itp);
+        if y_itp > 0.0 {
+            b = x_itp;
+            y_b = y_itp;
+        } else if y_itp < 0.0 {
+            a = x_itp;
+            y_a = y_itp;
+        } else if y_itp == 0.0 {
+            return Ok(x_itp);
+        } else {
+            return Err(SolveError::NonFinite);
+        }
+        scaled_epsilon *= 0.5;
+    }
+
+    Ok((a + b) * 0.5)
+}
+
+/// Solve f(x) = 0 where x belongs to [a, b] with Newton's method, starting from `guess`.
+///
+/// Falls back to ITP on the remaining bracket when a step leaves it or the iterations run out.
+/// `(y_a, y_b)` are the values of `f` at the ends of the bracket.
+fn solve_newton(
+    (mut a, mut b): (f64, f64),
+    (mut y_a, mut y_b): (f64, f64),
+    guess: f64,
+    mut f: impl FnMut(f64) -> (f64, f64),
+    config: &SolverConfig,
+) -> Result<f64, SolveError> {
+    let epsilon = tolerance(config, (a, b));
+
+    if let Some(root) = check_bracket((a, b), (y_a, y_b), epsilon)? {
+        return Ok(root);
+    }
+
+    let mut x = if a < guess && guess < b {
+        guess
+    } else {
+        0.5 * (a + b)
+    };
+    for _ in 0..config.max_iterations {
+        let (y, dy) = f(x);
+        if !y.is_finite() || !dy.is_finite() {
+            return Err(SolveError::NonFinite);
+        }
+
+        if y > 0.0 {
+            b = x;
+            y_b = y;
+        } else if y < 0.0 {
+            a = x;
+            y_a = y;
+        } else {
+            return Ok(x);
+        }
+
+        let next = x - y / dy;
+        if dy <= 0.0 || next <= a || next >= b {
+            break;
+        }
+        if (next - x).abs() < epsilon {
+            return Ok(next);
+        }
+        x = next;
+    }
+
+    solve_itp((a, b), (y_a, y_b), |x| f(x).0, config)
+}
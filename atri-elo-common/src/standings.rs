@@ -0,0 +1,163 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
+
+use crate::{EloError, PlayerId};
+
+/// Whether a higher or a lower score is better.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoreOrder {
+    /// A higher score is better, e.g. total score or accuracy.
+    #[default]
+    HigherIsBetter,
+    /// A lower score is better, e.g. miss count.
+    LowerIsBetter,
+}
+
+/// Standings of a contest in `(player_id, lo, hi)` order, where `lo..=hi` is the range of ranks shared by tied players.
+pub type Standings<Id = u64> = Vec<(Id, u64, u64)>;
+
+/// Calculate standings from contest scores, sorting the scores from best to worst.
+///
+/// The returned tuple follows `(player_id, lo, hi)` order, where `lo..=hi` is the range of ranks shared by tied players.
+///
+/// Fails if a score cannot be compared, e.g. a NaN.
+pub(crate) fn from_scores<Id: PlayerId, S: PartialOrd + Send>(
+    contest_scores: &mut [(Id, S)],
+    order: ScoreOrder,
+) -> Result<Standings<Id>, EloError<Id>> {
+    for (id, score) in contest_scores.iter() {
+        if score.partial_cmp(score).is_none() {
+            return Err(EloError::InvalidScore(id.clone()));
+        }
+    }
+    Ok(rank_scores(contest_scores, order))
+}
+
+/// Calculate standings from scores that are known to be comparable, as in [`from_scores`].
+pub(crate) fn rank_scores<Id: PlayerId, S: PartialOrd + Send>(
+    contest_scores: &mut [(Id, S)],
+    order: ScoreOrder,
+) -> Standings<Id> {
+    if contest_scores.is_empty() {
+        return Vec::new();
+    }
+
+    let mut standings = Vec::with_capacity(contest_scores.len());
+    let raw = contest_scores;
+    raw.par_sort_unstable_by(|a, b| {
+        let ordering = b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal);
+        match order {
+            ScoreOrder::HigherIsBetter => ordering,
+            ScoreOrder::LowerIsBetter => ordering.reverse(),
+        }
+    });
+    let mut rank_app = 1u64;
+    let mut rank_int = 1u64;
+    standings.push((raw[0].0.clone(), 1, 0));
+    for (i, (id, score)) in raw.iter().enumerate().skip(1) {
+        rank_int += 1;
+        if *score != raw[i - 1].1 {
+            rank_app = rank_int;
+        }
+        standings.push((id.clone(), rank_app, 0));
+    }
+    rank_app = rank_int;
+    standings.last_mut().unwrap().2 = rank_app;
+    for (i, (_, score)) in raw.iter().enumerate().rev().skip(1) {
+        rank_int -= 1;
+        if *score != raw[i + 1].1 {
+            rank_app = rank_int;
+        }
+        standings[i].2 = rank_app;
+    }
+    standings
+}
+
+/// Sort standings by rank and check that they are consistent.
+///
+/// Tied players must share the same `lo..=hi`, which must span exactly as many ranks as there are tied players.
+///
+/// Fails if there are fewer than two players, a player appears more than once or the ranks are inconsistent.
+pub(crate) fn check<Id: PlayerId>(standings: &mut [(Id, u64, u64)]) -> Result<(), EloError<Id>> {
+    if standings.len() < 2 {
+        return Err(EloError::NotEnoughPlayers);
+    }
+    let mut ids = HashSet::with_capacity(standings.len());
+    for (id, _, _) in standings.iter() {
+        if !ids.insert(id) {
+            return Err(EloError::DuplicatePlayer(id.clone()));
+        }
+    }
+
+    check_ranks(standings)
+}
+
+/// Sort team standings by rank and check that they rank every team exactly once, and that every team has
+/// distinct members.
+///
+/// `standings` uses indices into `teams` as ids, and its ranks are checked as in [`check`].
+pub(crate) fn check_teams<Id: PlayerId>(
+    teams: &[Vec<Id>],
+    standings: &mut [(u64, u64, u64)],
+) -> Result<(), EloError<Id>> {
+    if standings.len() < 2 {
+        return Err(EloError::NotEnoughPlayers);
+    }
+    let ranked: HashSet<_> = standings.iter().map(|&(team, _, _)| team).collect();
+    if ranked.len() != standings.len()
+        || standings.len() != teams.len()
+        || ranked.iter().any(|&team| team >= teams.len() as u64)
+    {
+        return Err(EloError::InvalidStandings);
+    }
+    let mut ids = HashSet::new();
+    for (index, team) in teams.iter().enumerate() {
+        if team.is_empty() {
+            return Err(EloError::EmptyTeam(index as u64));
+        }
+        for id in team {
+            if !ids.insert(id) {
+                return Err(EloError::DuplicatePlayer(id.clone()));
+            }
+        }
+    }
+
+    check_ranks(standings)
+}
+
+fn check_ranks<T: Send, Id>(standings: &mut [(T, u64, u64)]) -> Result<(), EloError<Id>> {
+    standings.par_sort_unstable_by_key(|&(_, lo, hi)| (lo, hi));
+    let mut start = 0;
+    while start < standings.len() {
+        let (_, lo, hi) = standings[start];
+        let len = standings[start..]
+            .iter()
+            .take_while(|&&(_, l, h)| (l, h) == (lo, hi))
+            .count();
+        if lo != start as u64 + 1 || hi != (start + len) as u64 {
+            return Err(EloError::InvalidStandings);
+        }
+        start += len;
+    }
+    Ok(())
+}
+
+/// Recompute the ranks of standings that lost players, keeping tied players tied.
+pub(crate) fn rerank<Id>(standings: &mut [(Id, u64, u64)]) {
+    standings.sort_unstable_by_key(|&(_, lo, hi)| (lo, hi));
+    let mut start = 0;
+    while start < standings.len() {
+        let (_, lo, hi) = standings[start];
+        let len = standings[start..]
+            .iter()
+            .take_while(|&&(_, l, h)| (l, h) == (lo, hi))
+            .count();
+        for (_, lo, hi) in standings[start..start + len].iter_mut() {
+            *lo = start as u64 + 1;
+            *hi = (start + len) as u64;
+        }
+        start += len;
+    }
+}
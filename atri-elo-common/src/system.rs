@@ -0,0 +1,56 @@
+use std::error::Error;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{standings, EloError, PlayerId, ScoreOrder, Standings};
+
+/// A rating system which processes contests one after another.
+///
+/// Implementors are (de)serializable, so that the state of any backend can be saved and restored the same way.
+/// Players are identified by `Id`, `u64` by default.
+pub trait RatingSystem<Id: PlayerId = u64>: Serialize + DeserializeOwned {
+    /// The error returned when a contest cannot be processed.
+    type Error: Error + From<EloError<Id>>;
+
+    /// Update ratings according to the explicit standings of the provided contest.
+    ///
+    /// `standings` follows `(player_id, lo, hi)` order, where `lo..=hi` is the range of ranks shared by tied players.
+    /// `time` is the time of the contest in seconds, e.g. a unix timestamp.
+    ///
+    /// Returns the partcipants' performance and rating in `(player_id, perf, rating)` order.
+    fn update_standings(
+        &mut self,
+        standings: Standings<Id>,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, Self::Error>;
+
+    /// Update ratings according to the result of the provided contest.
+    ///
+    /// `contest_scores` follows `(player_id, score)` order, where a higher score is better.
+    fn update<S: PartialOrd + Send>(
+        &mut self,
+        contest_scores: Vec<(Id, S)>,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, Self::Error> {
+        self.update_with_order(contest_scores, ScoreOrder::HigherIsBetter, time)
+    }
+
+    /// Update ratings according to the result of the provided contest, ranked by the given score order.
+    fn update_with_order<S: PartialOrd + Send>(
+        &mut self,
+        mut contest_scores: Vec<(Id, S)>,
+        order: ScoreOrder,
+        time: u64,
+    ) -> Result<Vec<(Id, f64, f64)>, Self::Error> {
+        let standings = standings::from_scores(&mut contest_scores, order)?;
+        self.update_standings(standings, time)
+    }
+
+    /// Get all players' rating.
+    ///
+    /// The returned tuple follows `(player_id, rating)` order.
+    fn get_ratings(&self) -> Vec<(Id, f64)>;
+
+    /// Get the rating of the specified player.
+    fn get_rating_of(&self, id: &Id) -> Option<f64>;
+}
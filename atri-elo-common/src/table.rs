@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::COEFF;
+
+/// Beyond this many δ away from every player, `tanh` is saturated to ±1 in f64.
+const SATURATION: f64 = 20.0;
+
+/// Ties of at least this many players get a table of their own instead of being summed exactly.
+const MIN_TABULATED_TIE: usize = 16;
+
+/// A cubic Hermite table of the contest-wide sum `T(x) = Σ tanh(COEFF (x - μ_π) / 2δ) / δ`.
+///
+/// The performance equation of a participant ranked in `lo..=hi` is
+/// `T(x) + T_tie(x) + Σ_{j < hi} 1/δ_j - Σ_{j ≥ lo - 1} 1/δ_j`, where `T_tie` only sums over the tied players.
+/// Looking `T` up in the table makes solving it independent of the contest size.
+/// Large ties tabulate their `T_tie` the same way, keyed by the index of their first player.
+pub(crate) struct PerfTable {
+    start: f64,
+    step: f64,
+    values: Vec<f64>,
+    slopes: Vec<f64>,
+    saturation: f64,
+    inv_delta_prefix: Vec<f64>,
+    ties: HashMap<usize, PerfTable>,
+}
+
+impl PerfTable {
+    /// Tabulate `T` with the given `step` from the players' `(delta, mu_pi)` and `(lo, hi)` ranks in rank order.
+    pub(crate) fn new(
+        player_data: &[(f64, f64)],
+        ranks: impl IntoIterator<Item = (u64, u64)>,
+        step: f64,
+    ) -> PerfTable {
+        let mut table = PerfTable::tabulate(player_data, step);
+        for (lo, hi) in ranks {
+            let (lo, hi) = (lo as usize - 1, hi as usize);
+            if hi - lo >= MIN_TABULATED_TIE && !table.ties.contains_key(&lo) {
+                let tie = PerfTable::tabulate(&player_data[lo..hi], step);
+                table.ties.insert(lo, tie);
+            }
+        }
+        table
+    }
+
+    fn tabulate(player_data: &[(f64, f64)], step: f64) -> PerfTable {
+        let mut inv_delta_prefix = Vec::with_capacity(player_data.len() + 1);
+        inv_delta_prefix.push(0.0);
+        for &(delta, _) in player_data {
+            inv_delta_prefix.push(inv_delta_prefix.last().unwrap() + delta.recip());
+        }
+
+        let (mut lo, mut hi, mut max_delta) = (f64::INFINITY, f64::NEG_INFINITY, 0f64);
+        for &(delta, mu_pi) in player_data {
+            lo = lo.min(mu_pi);
+            hi = hi.max(mu_pi);
+            max_delta = max_delta.max(delta);
+        }
+        let start = lo - SATURATION * max_delta;
+        let len = ((hi - start + SATURATION * max_delta) / step).ceil() as usize + 1;
+
+        let (values, slopes) = (0..len)
+            .into_par_iter()
+            .map(|k| {
+                let x = start + k as f64 * step;
+                let mut value = 0.0;
+                let mut slope = 0.0;
+                for &(delta, mu_pi) in player_data {
+                    let t = (COEFF * (x - mu_pi) / (2.0 * delta)).tanh();
+                    value += t / delta;
+                    slope += COEFF * (1.0 - t * t) / (2.0 * delta * delta);
+                }
+                (value, slope)
+            })
+            .unzip();
+
+        PerfTable {
+            start,
+            step,
+            values,
+            slopes,
+            saturation: *inv_delta_prefix.last().unwrap(),
+            inv_delta_prefix,
+            ties: HashMap::new(),
+        }
+    }
+
+    /// Interpolate `T(x)` and its derivative.
+    fn eval(&self, x: f64) -> (f64, f64) {
+        let pos = (x - self.start) / self.step;
+        if pos <= 0.0 {
+            return (-self.saturation, 0.0);
+        }
+        let k = pos.floor() as usize;
+        if k + 1 >= self.values.len() {
+            return (self.saturation, 0.0);
+        }
+
+        let t = pos - k as f64;
+        let (t2, t3) = (t * t, t * t * t);
+        let (v_0, v_1) = (self.values[k], self.values[k + 1]);
+        let (m_0, m_1) = (self.slopes[k], self.slopes[k + 1]);
+        let value = (2.0 * t3 - 3.0 * t2 + 1.0) * v_0
+            + (t3 - 2.0 * t2 + t) * self.step * m_0
+            + (-2.0 * t3 + 3.0 * t2) * v_1
+            + (t3 - t2) * self.step * m_1;
+        let slope = (6.0 * t2 - 6.0 * t) * (v_0 - v_1) / self.step
+            + (3.0 * t2 - 4.0 * t + 1.0) * m_0
+            + (3.0 * t2 - 2.0 * t) * m_1;
+        (value, slope)
+    }
+
+    /// Evaluate the performance equation of a participant ranked in `lo..=hi` and its derivative.
+    pub(crate) fn perf_equation(
+        &self,
+        x: f64,
+        player_data: &[(f64, f64)],
+        (lo, hi): (u64, u64),
+    ) -> (f64, f64) {
+        let (lo, hi) = (lo as usize - 1, hi as usize);
+        let (mut value, mut slope) = self.eval(x);
+        match self.ties.get(&lo) {
+            Some(tie) => {
+                let (tie_value, tie_slope) = tie.eval(x);
+                value += tie_value;
+                slope += tie_slope;
+            }
+            None => {
+                for &(delta, mu_pi) in &player_data[lo..hi] {
+                    let t = (COEFF * (x - mu_pi) / (2.0 * delta)).tanh();
+                    value += t / delta;
+                    slope += COEFF * (1.0 - t * t) / (2.0 * delta * delta);
+                }
+            }
+        }
+        value += self.inv_delta_prefix[hi] - (self.saturation - self.inv_delta_prefix[lo]);
+        (value, slope)
+    }
+}
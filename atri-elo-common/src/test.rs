@@ -1,4 +1,13 @@
-use crate::solve_itp;
+use crate::{
+    glicko,
+    simulation::spearman,
+    solver::solve_itp,
+    standings,
+    topcoder::{erf, inverse_normal_cdf},
+    Codeforces, ContestRecord, EloError, EloMmr, Glicko2, InactivityPolicy, PerformanceModel,
+    RatingSystem, ScoreOrder, Simulation, SnapshotError, SolveError, SolverConfig, SolverMethod,
+    TeamAggregation, TopCoder, SNAPSHOT_VERSION,
+};
 
 #[test]
 fn solve_itp_test_1() {
@@ -6,5 +15,963 @@ fn solve_itp_test_1() {
         x.powi(3) - x - 2.0
     }
 
-    assert!(f(dbg!(solve_itp((1.0, 2.0), f))) < 1e-10);
+    let root = solve_itp((1.0, 2.0), (f(1.0), f(2.0)), f, &SolverConfig::default()).unwrap();
+    assert!(f(root) < 1e-10, "root {root}");
+}
+
+#[test]
+fn player_state_test_1() {
+    let mut system = EloMmr::default();
+    system
+        .update(vec![(1, 300), (2, 200), (3, 200)], 0)
+        .unwrap();
+    system.update(vec![(1, 100), (3, 200)], 1).unwrap();
+
+    let state = system.get_player(&1).unwrap();
+    assert_eq!(state.num_contests, 2);
+    assert_eq!(state.perfs.len(), 3);
+    assert_eq!(state.weights.len(), 3);
+    assert_eq!(Some(state.mu), system.get_rating_of(&1));
+    assert!(system.get_player(&4).is_none());
+
+    let mut ids: Vec<_> = system.iter_players().map(|(id, _)| id).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![1, 2, 3]);
+}
+
+#[test]
+fn sigma_test_1() {
+    // Each performance is 1/β² of evidence, so σ shrinks after a contest instead of only growing by diffusion.
+    let mut system = EloMmr::default();
+    system.update(vec![(1, 300), (2, 200)], 0).unwrap();
+
+    let diffused = 350f64.hypot(80.0);
+    let expected = (diffused.powi(-2) + 200f64.powi(-2)).sqrt().recip();
+    assert!((system.get_player(&1).unwrap().sigma - expected).abs() < 1e-9);
+
+    for time in 1..50 {
+        system.update(vec![(1, 300), (2, 200)], time).unwrap();
+    }
+    // Regular players settle where diffusion and evidence balance, 1/σ² = 1/(σ² + γ²) + 1/β².
+    let sigma = system.get_player(&1).unwrap().sigma;
+    assert!((sigma.powi(-2) - sigma.hypot(80.0).powi(-2) - 200f64.powi(-2)).abs() < 1e-9);
+}
+
+#[test]
+fn rollback_test_1() {
+    let mut system = EloMmr::default().with_checkpoint_limit(2);
+    system.update(vec![(1, 300), (2, 200)], 0).unwrap();
+    let before = system.get_player(&1).unwrap();
+
+    system
+        .update(vec![(1, 100), (2, 200), (3, 150)], 1)
+        .unwrap();
+    assert_eq!(system.num_checkpoints(), 2);
+
+    let mut contest = system.rollback().unwrap();
+    contest.standings.sort_unstable();
+    assert_eq!(contest.time, 1);
+    assert_eq!(contest.teams, None);
+    assert_eq!(contest.standings, vec![(1, 3, 3), (2, 1, 1), (3, 2, 2)]);
+    assert_eq!(system.get_player(&1).unwrap(), before);
+    assert!(system.get_player(&3).is_none());
+
+    system.rollback().unwrap();
+    assert!(system.get_player(&1).is_none());
+    assert!(system.rollback().is_none());
+}
+
+#[test]
+fn drift_test_1() {
+    const DAY: u64 = 86400;
+
+    let mut system = EloMmr::default().with_drift_per_sec(1.0 / DAY as f64);
+    system
+        .update(vec![(1, 300), (2, 200), (3, 100)], 0)
+        .unwrap();
+    system.update(vec![(1, 300), (3, 100)], DAY).unwrap();
+    system
+        .update(vec![(1, 300), (2, 200), (3, 100)], 400 * DAY)
+        .unwrap();
+
+    let regular = system.get_player(&1).unwrap();
+    let returning = system.get_player(&2).unwrap();
+    assert!(returning.sigma > regular.sigma);
+}
+
+#[test]
+fn standings_test_1() {
+    let mut scores = vec![(1, 100), (2, 300), (3, 200), (4, 200)];
+    let mut result = standings::from_scores(&mut scores, ScoreOrder::HigherIsBetter).unwrap();
+    result.sort_unstable();
+    assert_eq!(result, vec![(1, 4, 4), (2, 1, 1), (3, 2, 3), (4, 2, 3)]);
+
+    let mut misses = vec![(1, 5.0), (2, 0.0), (3, 2.5), (4, 2.5)];
+    let mut result = standings::from_scores(&mut misses, ScoreOrder::LowerIsBetter).unwrap();
+    result.sort_unstable();
+    assert_eq!(result, vec![(1, 4, 4), (2, 1, 1), (3, 2, 3), (4, 2, 3)]);
+
+    let mut trailing = vec![(1, 300), (2, 200), (3, 200)];
+    let mut result = standings::from_scores(&mut trailing, ScoreOrder::HigherIsBetter).unwrap();
+    result.sort_unstable();
+    assert_eq!(result, vec![(1, 1, 1), (2, 2, 3), (3, 2, 3)]);
+
+    let mut nan = vec![(1, 1.0), (2, f64::NAN)];
+    assert_eq!(
+        standings::from_scores(&mut nan, ScoreOrder::HigherIsBetter),
+        Err(EloError::InvalidScore(2))
+    );
+}
+
+#[test]
+fn standings_test_2() {
+    let mut explicit = EloMmr::default();
+    let mut scored = EloMmr::default();
+    explicit
+        .update_standings(vec![(1, 1, 1), (2, 2, 3), (3, 2, 3)], 0)
+        .unwrap();
+    scored
+        .update_with_order(
+            vec![(1, 0.1), (2, 0.7), (3, 0.7)],
+            ScoreOrder::LowerIsBetter,
+            0,
+        )
+        .unwrap();
+    for id in 1..=3 {
+        assert_eq!(explicit.get_player(&id), scored.get_player(&id));
+    }
+
+    assert_eq!(
+        explicit.update_standings(vec![(1, 1, 1), (2, 1, 2)], 1),
+        Err(EloError::InvalidStandings)
+    );
+    assert_eq!(
+        explicit.update_standings(vec![(1, 1, 1), (2, 3, 3)], 1),
+        Err(EloError::InvalidStandings)
+    );
+    assert_eq!(
+        Glicko2::default().update_standings(vec![(1, 1, 2), (2, 1, 2), (3, 1, 2)], 0),
+        Err(EloError::InvalidStandings)
+    );
+}
+
+#[test]
+fn rating_system_test_1() {
+    fn run<T: RatingSystem>(mut system: T) -> T {
+        system
+            .update(vec![(1, 300), (2, 200), (3, 100)], 0)
+            .unwrap();
+        system
+    }
+
+    let system = run(EloMmr::default());
+    assert!(system.get_rating_of(&1).unwrap() > system.get_rating_of(&3).unwrap());
+}
+
+#[test]
+fn glicko_test_1() {
+    // The example from Glickman's "Example of the Glicko-2 system".
+    let games = [
+        (-100.0 / 173.7178, 30.0 / 173.7178, 1.0),
+        (50.0 / 173.7178, 100.0 / 173.7178, 0.0),
+        (200.0 / 173.7178, 300.0 / 173.7178, 0.0),
+    ];
+    let (mu, phi, sigma, _) = glicko::rate((0.0, 200.0 / 173.7178, 0.06), 0.5, &games).unwrap();
+
+    assert!((mu * 173.7178 + 1500.0 - 1464.06).abs() < 0.01);
+    assert!((phi * 173.7178 - 151.52).abs() < 0.01);
+    assert!((sigma - 0.05999).abs() < 1e-5);
+
+    assert!(Glicko2::try_new(0.5, 1500.0, 350.0, 0.06).is_ok());
+    assert_eq!(
+        Glicko2::try_new(0.0, 1500.0, 350.0, 0.06).unwrap_err(),
+        EloError::InvalidParameter("tau")
+    );
+    assert_eq!(
+        Glicko2::try_new(0.5, f64::NAN, 350.0, 0.06).unwrap_err(),
+        EloError::InvalidParameter("rating_init")
+    );
+    assert_eq!(
+        Glicko2::try_new(0.5, 1500.0, f64::INFINITY, 0.06).unwrap_err(),
+        EloError::InvalidParameter("rd_init")
+    );
+    assert_eq!(
+        Glicko2::try_new(0.5, 1500.0, 350.0, -0.06).unwrap_err(),
+        EloError::InvalidParameter("volatility_init")
+    );
+}
+
+#[test]
+fn glicko_test_2() {
+    let mut system = Glicko2::default();
+    let result = system
+        .update(vec![(1, 300), (2, 200), (3, 200), (4, 100)], 0)
+        .unwrap();
+    assert_eq!(result.len(), 4);
+
+    let rating_of = |id| system.get_rating_of(&id).unwrap();
+    assert!(rating_of(1) > rating_of(2));
+    assert!((rating_of(2) - rating_of(3)).abs() < 1e-9);
+    assert!(rating_of(3) > rating_of(4));
+    assert!(system.get_rd_of(&1).unwrap() < 350.0);
+}
+
+#[test]
+fn codeforces_test_1() {
+    let mut system = Codeforces::default();
+    let result = system
+        .update(vec![(1, 300), (2, 200), (3, 200), (4, 100)], 0)
+        .unwrap();
+    assert_eq!(result.len(), 4);
+
+    let rating_of = |id| system.get_rating_of(&id).unwrap();
+    assert!(rating_of(1) > 1500.0);
+    assert!((rating_of(2) - rating_of(3)).abs() < 1e-9);
+    assert!(rating_of(4) < 1500.0);
+    assert!(
+        system
+            .get_ratings()
+            .iter()
+            .map(|(_, r)| r - 1500.0)
+            .sum::<f64>()
+            < 0.0
+    );
+}
+
+#[test]
+fn topcoder_test_1() {
+    let mut system = TopCoder::default();
+    system
+        .update(vec![(1, 300), (2, 200), (3, 200), (4, 100)], 0)
+        .unwrap();
+
+    let rating_of = |id| system.get_rating_of(&id).unwrap();
+    assert!(rating_of(1) > rating_of(2));
+    assert!((rating_of(2) - rating_of(3)).abs() < 1e-9);
+    assert!(rating_of(3) > rating_of(4));
+    assert!((rating_of(1) - 1200.0).abs() <= 150.0 + 1500.0 / 2.0);
+}
+
+#[test]
+fn topcoder_test_2() {
+    assert!((erf(1.0) - 0.8427007929).abs() < 1e-6);
+    assert!((erf(-0.5) + 0.5204998778).abs() < 1e-6);
+    assert!((inverse_normal_cdf(0.975) - 1.959963985).abs() < 1e-6);
+    assert!((inverse_normal_cdf(0.01) + 2.326347874).abs() < 1e-6);
+}
+
+#[test]
+fn performance_model_test_1() {
+    let contests = [
+        vec![(1, 300), (2, 200), (3, 100)],
+        vec![(1, 300), (2, 200), (3, 100)],
+        vec![(1, 100), (2, 200), (3, 300)],
+    ];
+
+    let mut logistic = EloMmr::default();
+    let mut gaussian = EloMmr::default().with_performance_model(PerformanceModel::Gaussian);
+    for (time, contest) in contests.iter().enumerate() {
+        logistic.update(contest.clone(), time as u64).unwrap();
+        gaussian.update(contest.clone(), time as u64).unwrap();
+    }
+
+    // The outlier last contest pulls the Gaussian rating further down.
+    let logistic = logistic.get_rating_of(&1).unwrap();
+    let gaussian = gaussian.get_rating_of(&1).unwrap();
+    assert!(gaussian < logistic);
+}
+
+#[test]
+fn max_history_test_1() {
+    let mut bounded = EloMmr::default().with_max_history(4);
+    let mut unbounded = EloMmr::default();
+    for time in 0..20 {
+        let contest = vec![(1, 300 + time), (2, 200), (3, 100 + 20 * time)];
+        bounded.update(contest.clone(), time as u64).unwrap();
+        unbounded.update(contest, time as u64).unwrap();
+    }
+
+    let state = bounded.get_player(&1).unwrap();
+    assert_eq!(state.num_contests, 20);
+    assert_eq!(state.perfs.len(), 5);
+    assert_eq!(state.weights.len(), 5);
+
+    // Folded performances are old and lightly weighted, so the rating barely moves.
+    let exact = unbounded.get_rating_of(&1).unwrap();
+    assert!((state.mu - exact).abs() < 1.0);
+}
+
+#[test]
+fn approximation_test_1() {
+    let mut seed = 42u64;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as i64
+    };
+    let contests: Vec<Vec<(u64, i64)>> = (0..3)
+        .map(|_| (0..500).map(|id| (id, next() % 1000)).collect())
+        .collect();
+
+    let mut exact = EloMmr::default();
+    let mut approx = EloMmr::default().with_approximation(100, 10.0);
+    for (time, contest) in contests.into_iter().enumerate() {
+        let mut exact_result = exact.update(contest.clone(), time as u64).unwrap();
+        let mut approx_result = approx.update(contest, time as u64).unwrap();
+        exact_result.sort_unstable_by_key(|v| v.0);
+        approx_result.sort_unstable_by_key(|v| v.0);
+        for (e, a) in exact_result.iter().zip(approx_result.iter()) {
+            assert_eq!(e.0, a.0);
+            assert!((e.1 - a.1).abs() < 1e-3);
+            assert!((e.2 - a.2).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn approximation_test_2() {
+    // Half of the players tie with a zero score, which is tabulated instead of summed for each of them.
+    let contest: Vec<(u64, u64)> = (0..400)
+        .map(|id| (id, if id % 2 == 0 { 0 } else { id * 7 % 300 }))
+        .collect();
+
+    let mut exact = EloMmr::default();
+    let mut approx = EloMmr::default().with_approximation(100, 10.0);
+    let mut exact_result = exact.update(contest.clone(), 0).unwrap();
+    let mut approx_result = approx.update(contest, 0).unwrap();
+    exact_result.sort_unstable_by_key(|v| v.0);
+    approx_result.sort_unstable_by_key(|v| v.0);
+    for (e, a) in exact_result.iter().zip(approx_result.iter()) {
+        assert_eq!(e.0, a.0);
+        assert!((e.1 - a.1).abs() < 1e-3);
+        assert!((e.2 - a.2).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn solver_test_1() {
+    let newton = SolverConfig {
+        method: SolverMethod::Newton,
+        ..SolverConfig::default()
+    };
+
+    let mut itp_system = EloMmr::default();
+    let mut newton_system = EloMmr::default().with_solver(newton);
+    for time in 0..3 {
+        let contest = vec![(1, 300), (2, 200 + 100 * time), (3, 100)];
+        itp_system.update(contest.clone(), time as u64).unwrap();
+        newton_system.update(contest, time as u64).unwrap();
+    }
+    for id in 1..=3 {
+        let itp = itp_system.get_rating_of(&id).unwrap();
+        let newton = newton_system.get_rating_of(&id).unwrap();
+        assert!((itp - newton).abs() < 1e-6);
+    }
+
+    // A root outside of the bracket is reported instead of silently returning garbage.
+    let result = solve_itp(
+        (1.0, 2.0),
+        (11.0, 12.0),
+        |x| x + 10.0,
+        &SolverConfig::default(),
+    );
+    assert_eq!(result, Err(SolveError::Bracket { a: 1.0, b: 2.0 }));
+
+    let few_iterations = SolverConfig {
+        max_iterations: 3,
+        ..SolverConfig::default()
+    };
+    let f = |x: f64| x.powi(3) - x - 2.0;
+    let result = solve_itp((-1e4, 1e4), (f(-1e4), f(1e4)), f, &few_iterations);
+    assert_eq!(result, Err(SolveError::NoConvergence));
+}
+
+#[test]
+fn error_test_1() {
+    assert_eq!(
+        EloMmr::<u64>::try_new(1.0, f64::NAN, 80.0, 1500.0, 350.0).unwrap_err(),
+        EloError::InvalidParameter("beta")
+    );
+    assert_eq!(
+        EloMmr::<u64>::try_new(1.0, 200.0, 80.0, 1500.0, 0.0).unwrap_err(),
+        EloError::InvalidParameter("sigma_init")
+    );
+
+    let mut system = EloMmr::try_new(1.0, 200.0, 80.0, 1500.0, 350.0).unwrap();
+    assert_eq!(
+        system.update(vec![(1, 300), (2, 200), (1, 100)], 0),
+        Err(EloError::DuplicatePlayer(1))
+    );
+    assert_eq!(
+        system.update(vec![(1, 300)], 0),
+        Err(EloError::NotEnoughPlayers)
+    );
+    assert!(system.get_player(&1).is_none());
+    assert_eq!(system.update(Vec::<(u64, i64)>::new(), 0), Ok(Vec::new()));
+}
+
+#[test]
+fn bracket_test_1() {
+    let systems = [
+        EloMmr::try_new(1.0, 2.0, 0.8, 1e6, 3.5).unwrap(),
+        EloMmr::try_new(1.0, 2e5, 8e4, -1e7, 3.5e5).unwrap(),
+        EloMmr::try_new(1.0, 1e-3, 1e-3, 0.0, 1e4).unwrap(),
+    ];
+
+    for mut system in systems {
+        for time in 0..5u64 {
+            let contest = vec![(1, 300), (2, 200), (3, 100 + 150 * (time % 2) as i64)];
+            for (_, perf, rating) in system.update(contest, time).unwrap() {
+                assert!(perf.is_finite() && rating.is_finite());
+            }
+        }
+        assert!(system.get_rating_of(&1).unwrap() > system.get_rating_of(&2).unwrap());
+    }
+}
+
+#[test]
+fn team_test_1() {
+    let mut individual = EloMmr::default();
+    let mut team = EloMmr::default().with_team_aggregation(TeamAggregation::Sum);
+    individual
+        .update(vec![(1, 300), (2, 200), (3, 200)], 0)
+        .unwrap();
+    team.update_teams(vec![(vec![1], 300), (vec![2], 200), (vec![3], 200)], 0)
+        .unwrap();
+    for id in 1..=3 {
+        let (a, b) = (
+            individual.get_player(&id).unwrap(),
+            team.get_player(&id).unwrap(),
+        );
+        assert!((a.mu - b.mu).abs() < 1e-9);
+        assert!((a.sigma - b.sigma).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn team_test_2() {
+    let mut system = EloMmr::default().with_checkpoint_limit(1);
+    system.update(vec![(1, 300), (2, 200)], 0).unwrap();
+    let veteran = system.get_player(&1).unwrap();
+
+    let result = system
+        .update_team_standings(vec![vec![3, 4], vec![1, 5]], vec![(0, 2, 2), (1, 1, 1)], 1)
+        .unwrap();
+    assert_eq!(result.len(), 4);
+
+    // The winning team's members both gain, and the newcomer gains more than the veteran.
+    let gain = |id: u64, before: f64| system.get_rating_of(&id).unwrap() - before;
+    assert!(gain(1, veteran.mu) > 0.0);
+    assert!(gain(5, 1500.0) > gain(1, veteran.mu));
+    assert!(gain(3, 1500.0) < 0.0);
+
+    let contest = system.rollback().unwrap();
+    assert_eq!(contest.teams, Some(vec![vec![3, 4], vec![1, 5]]));
+    assert_eq!(system.get_player(&1).unwrap(), veteran);
+    assert!(system.get_player(&5).is_none());
+
+    assert_eq!(
+        system.update_team_standings(vec![vec![1], vec![]], vec![(0, 1, 1), (1, 2, 2)], 1),
+        Err(EloError::EmptyTeam(1))
+    );
+    assert_eq!(
+        system.update_teams(vec![(vec![], f64::NAN), (vec![1], 1.0)], 1),
+        Err(EloError::EmptyTeam(0))
+    );
+    assert_eq!(
+        system.update_teams(vec![(vec![1], 1.0), (vec![2, 3], f64::NAN)], 1),
+        Err(EloError::InvalidScore(2))
+    );
+    assert_eq!(
+        system.update_team_standings(vec![vec![1], vec![1, 2]], vec![(0, 1, 1), (1, 2, 2)], 1),
+        Err(EloError::DuplicatePlayer(1))
+    );
+    assert_eq!(
+        system.update_team_standings(vec![vec![1], vec![2]], vec![(0, 1, 1), (2, 2, 2)], 1),
+        Err(EloError::InvalidStandings)
+    );
+}
+
+#[test]
+fn weight_test_1() {
+    let mut full = EloMmr::default();
+    let mut casual = EloMmr::default().with_checkpoint_limit(1);
+    full.update(vec![(1, 300), (2, 200)], 0).unwrap();
+    casual
+        .apply(ContestRecord::new(vec![(1, 1, 1), (2, 2, 2)], 0).with_weight(0.25))
+        .unwrap();
+
+    let (full, casual_1) = (full.get_player(&1).unwrap(), casual.get_player(&1).unwrap());
+    assert!(casual_1.mu > 1500.0);
+    assert!(casual_1.mu - 1500.0 < full.mu - 1500.0);
+    assert!(casual_1.sigma > full.sigma);
+
+    assert_eq!(casual.rollback().unwrap().weight, 0.25);
+    assert_eq!(
+        casual.apply(ContestRecord::new(vec![(1, 1, 1), (2, 2, 2)], 0).with_weight(0.0)),
+        Err(EloError::InvalidWeight)
+    );
+}
+
+#[test]
+fn predict_test_1() {
+    let mut system = EloMmr::default();
+    system
+        .update(vec![(1, 300), (2, 200), (3, 100)], 0)
+        .unwrap();
+
+    assert!((system.win_probability(&4, &5) - 0.5).abs() < 1e-12);
+    let p = system.win_probability(&1, &3);
+    assert!(p > 0.5);
+    assert!((p + system.win_probability(&3, &1) - 1.0).abs() < 1e-12);
+
+    let lineup = [1, 2, 3];
+    let distribution = system.rank_distribution(&2, &lineup);
+    assert_eq!(distribution.len(), 3);
+    assert!((distribution.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    let mean = distribution
+        .iter()
+        .enumerate()
+        .map(|(k, p)| (k + 1) as f64 * p)
+        .sum::<f64>();
+    assert!((mean - system.expected_rank(&2, &lineup)).abs() < 1e-12);
+
+    let head_to_head = system.rank_distribution(&1, &[3]);
+    assert!((head_to_head[0] - p).abs() < 1e-12);
+    assert!(system.expected_rank(&1, &lineup) < system.expected_rank(&3, &lineup));
+}
+
+#[test]
+fn breakdown_test_1() {
+    let mut plain = EloMmr::default();
+    let mut explained = EloMmr::default();
+    plain.update(vec![(1, 300), (2, 200)], 0).unwrap();
+    explained.update(vec![(1, 300), (2, 200)], 0).unwrap();
+
+    let contest = ContestRecord::new(vec![(1, 3, 3), (2, 1, 1), (3, 2, 2)], 1);
+    let result = plain.apply(contest.clone()).unwrap();
+    let breakdowns = explained.apply_with_breakdown(contest).unwrap();
+
+    for ((id, perf, rating), (other, breakdown)) in result.into_iter().zip(breakdowns) {
+        assert_eq!(id, other);
+        assert_eq!(perf, breakdown.perf);
+        assert_eq!(rating, breakdown.rating);
+        assert_eq!(breakdown.opponents.len(), 3);
+        assert_eq!(
+            breakdown.history.len(),
+            explained.get_player(&id).unwrap().num_contests
+        );
+
+        let perf_sum = breakdown
+            .opponents
+            .iter()
+            .map(|(_, term)| term)
+            .sum::<f64>();
+        let rating_sum = breakdown.prior + breakdown.history.iter().sum::<f64>();
+        assert!(perf_sum.abs() < 1e-6);
+        assert!(rating_sum.abs() < 1e-6);
+        if id == 1 {
+            // Everyone else outperformed player 1, pulling the performance down.
+            assert!(breakdown
+                .opponents
+                .iter()
+                .all(|&(other, term)| other == 1 || term > 0.0));
+        }
+    }
+}
+
+#[test]
+fn display_rating_test_1() {
+    let mut system = EloMmr::default()
+        .with_display_rating(2.0)
+        .with_provisional(150.0, 2);
+    system.update(vec![(1, 300), (2, 200)], 0).unwrap();
+    for time in 1..10 {
+        system.update(vec![(1, 300), (3, 200)], time).unwrap();
+    }
+
+    let occasional = system.get_player(&2).unwrap();
+    assert_eq!(
+        system.get_display_rating_of(&2),
+        Some(occasional.mu - 2.0 * occasional.sigma)
+    );
+    assert!(system.get_rating_of(&2).unwrap() > system.get_rating_of(&3).unwrap());
+    assert!(system.get_display_rating_of(&2).unwrap() < system.get_display_rating_of(&3).unwrap());
+    assert_eq!(system.is_provisional(&2), Some(true));
+    assert_eq!(system.is_provisional(&3), Some(false));
+    assert_eq!(system.is_provisional(&4), None);
+    assert_eq!(system.get_display_ratings().len(), 3);
+
+    assert_eq!(
+        system.with_display_rating(f64::NAN).validate(),
+        Err(EloError::InvalidParameter("sigma_factor"))
+    );
+}
+
+#[test]
+fn inactivity_test_1() {
+    const DAY: u64 = 86400;
+
+    let mut decaying = EloMmr::default()
+        .with_display_rating(2.0)
+        .with_inactivity_policy(InactivityPolicy::IncreaseSigma {
+            grace_secs: 30 * DAY,
+            variance_per_sec: 100.0 / DAY as f64,
+        });
+    decaying.update(vec![(1, 300), (2, 200)], 0).unwrap();
+    decaying
+        .update(vec![(2, 300), (3, 200)], 100 * DAY)
+        .unwrap();
+    let mut hiding = decaying
+        .clone()
+        .with_inactivity_policy(InactivityPolicy::Hide {
+            after_secs: 30 * DAY,
+        });
+
+    let before = decaying.get_display_rating_of(&1).unwrap();
+    let state = decaying.get_player(&1).unwrap();
+    decaying.set_time(100 * DAY);
+    assert!(decaying.get_display_rating_of(&1).unwrap() < before);
+    let active = decaying.get_player(&2).unwrap();
+    assert_eq!(
+        decaying.get_display_rating_of(&2),
+        Some(active.mu - 2.0 * active.sigma)
+    );
+    assert_eq!(decaying.get_player(&1).unwrap(), state);
+    assert_eq!(decaying.get_ratings().len(), 3);
+
+    hiding.set_time(100 * DAY);
+    let mut visible: Vec<_> = hiding.get_ratings().into_iter().map(|(id, _)| id).collect();
+    visible.sort_unstable();
+    assert_eq!(visible, vec![2, 3]);
+    assert_eq!(hiding.is_active(&1), Some(false));
+    assert_eq!(hiding.get_player(&1).unwrap(), state);
+}
+
+#[test]
+fn season_test_1() {
+    let mut system = EloMmr::default().with_checkpoint_limit(1);
+    for time in 0..5 {
+        system.update(vec![(1, 300), (2, 200)], time).unwrap();
+    }
+    let (winner, loser) = (
+        system.get_player(&1).unwrap(),
+        system.get_player(&2).unwrap(),
+    );
+
+    system.soft_reset(5, 0.5, 100.0).unwrap();
+    assert_eq!(system.num_checkpoints(), 0);
+    assert_eq!(system.seasons().len(), 1);
+    assert_eq!(system.seasons()[0].ratings[0], (1, winner.mu));
+
+    let reset = system.get_player(&1).unwrap();
+    assert!((reset.mu - (1500.0 + 0.5 * (winner.mu - 1500.0))).abs() < 1e-9);
+    assert!(reset.sigma > winner.sigma);
+    assert!(system.get_rating_of(&2).unwrap() > loser.mu);
+
+    // The reset survives the next update instead of being solved away from the history.
+    system.update(vec![(1, 300), (3, 200)], 6).unwrap();
+    assert!(system.get_rating_of(&1).unwrap() < winner.mu);
+
+    assert_eq!(
+        system.soft_reset(7, 1.5, 0.0),
+        Err(EloError::InvalidParameter("fraction"))
+    );
+}
+
+#[test]
+fn merge_test_1() {
+    let mut system = EloMmr::default().with_checkpoint_limit(1);
+    let mut perfs = Vec::new();
+    for time in 0..6 {
+        let id = if time % 2 == 0 { 1 } else { 2 };
+        let result = system.update(vec![(id, 300), (3, 200)], time).unwrap();
+        perfs.push(result.iter().find(|r| r.0 == id).unwrap().1);
+    }
+
+    let single = system.get_player(&1).unwrap();
+    let merged = system.merge_players(1, 2).unwrap();
+    assert_eq!(merged.num_contests, 6);
+    assert_eq!(merged.perfs[1..], perfs[..]);
+    assert!(merged.mu > system.get_rating_of(&3).unwrap());
+    assert!(merged.sigma < single.sigma);
+    assert!(system.get_player(&2).is_none());
+    assert_eq!(system.num_checkpoints(), 0);
+
+    assert_eq!(system.merge_players(1, 2), Err(EloError::UnknownPlayer(2)));
+    assert_eq!(
+        system.merge_players(1, 1),
+        Err(EloError::DuplicatePlayer(1))
+    );
+
+    // Folded histories would be replayed from the wrong prior, so they are refused.
+    let mut folded = EloMmr::default().with_max_history(3);
+    for time in 0..5 {
+        folded.update(vec![(1, 300), (2, 200)], time).unwrap();
+    }
+    folded.update(vec![(3, 300), (2, 200)], 5).unwrap();
+    assert_eq!(
+        folded.merge_players(3, 1),
+        Err(EloError::IncompleteHistory(1))
+    );
+    assert_eq!(folded.get_player(&1).unwrap().num_contests, 5);
+
+    // Soft resets are replayed in place, so players can still be merged after a season ended.
+    system.soft_reset(6, 0.5, 100.0).unwrap();
+    let reset = system.get_player(&1).unwrap();
+    let result = system.update(vec![(4, 300), (3, 200)], 7).unwrap();
+    let merged = system.merge_players(1, 4).unwrap();
+    assert_eq!(merged.num_contests, 7);
+    for (perf, expected) in merged.perfs[1..].iter().zip(&reset.perfs[1..]) {
+        assert!(
+            (perf - expected).abs() < 1e-9,
+            "perf {perf}, expected {expected}"
+        );
+    }
+    assert_eq!(merged.perfs[7], result[0].1);
+
+    // Both players went through the reset, and played before and after it.
+    let mut season = EloMmr::default();
+    for time in 0..4 {
+        season.update(vec![(1, 300), (3, 200)], time).unwrap();
+        season.update(vec![(2, 300), (3, 200)], time).unwrap();
+        if time == 1 {
+            season.soft_reset(time, 0.5, 100.0).unwrap();
+        }
+    }
+    let into = season.get_player(&1).unwrap();
+    let merged = season.merge_players(1, 2).unwrap();
+    assert_eq!(merged.num_contests, 8);
+    assert!(merged.sigma < into.sigma);
+    assert!((merged.mu - into.mu).abs() < 100.0);
+}
+
+#[test]
+fn remove_player_test_1() {
+    let contests: Vec<Vec<(u64, i64)>> = vec![
+        vec![(1, 100), (2, 200)],
+        vec![(1, 100), (2, 200), (3, 150), (4, 300)],
+        vec![(1, 300), (4, 300)],
+        vec![(1, 250), (2, 200), (3, 150)],
+        vec![(1, 100), (2, 200), (3, 300), (4, 50)],
+    ];
+
+    let mut clean = EloMmr::default();
+    for (time, scores) in contests.iter().enumerate() {
+        let scores: Vec<_> = scores.iter().filter(|s| s.0 != 4).cloned().collect();
+        if scores.len() > 1 {
+            clean.update(scores, time as u64).unwrap();
+        }
+    }
+
+    for limit in [0, 10] {
+        let mut system = EloMmr::default()
+            .with_checkpoint_limit(limit)
+            .with_contest_log();
+        for (time, scores) in contests.iter().enumerate() {
+            system.update(scores.clone(), time as u64).unwrap();
+        }
+
+        let replayed = system.remove_player(4).unwrap();
+        assert_eq!(replayed.len(), 4);
+        assert!(replayed[1].result.is_empty());
+        assert_eq!(replayed[3].contest.standings.len(), 3);
+        assert!(system.get_player(&4).is_none());
+        for id in 1..=3 {
+            let rating = system.get_rating_of(&id).unwrap();
+            assert!((rating - clean.get_rating_of(&id).unwrap()).abs() < 1e-6);
+        }
+
+        assert_eq!(system.remove_player(4), Err(EloError::UnknownPlayer(4)));
+    }
+
+    assert_eq!(
+        EloMmr::default().remove_player(1),
+        Err(EloError::MissingContestLog)
+    );
+}
+
+#[test]
+fn remove_player_test_2() {
+    // The log is enabled after some contests, as when the server loads a stored system.
+    let mut clean = EloMmr::default();
+    let mut system = EloMmr::default();
+    for rated in [&mut clean, &mut system] {
+        rated.update(vec![(10, 300), (11, 200)], 0).unwrap();
+    }
+    let mut system = system.with_contest_log();
+    clean.update(vec![(1, 100), (10, 200)], 1).unwrap();
+    system
+        .update(vec![(1, 100), (3, 150), (10, 200)], 1)
+        .unwrap();
+    system.update(vec![(2, 100), (3, 150)], 2).unwrap();
+
+    let mut loaded = EloMmr::<u64>::from_binary(&system.to_binary().unwrap()).unwrap();
+    for system in [&mut system, &mut loaded] {
+        assert_eq!(system.remove_player(3).unwrap().len(), 2);
+        for id in [1, 10, 11] {
+            let rating = system.get_rating_of(&id).unwrap();
+            assert!((rating - clean.get_rating_of(&id).unwrap()).abs() < 1e-6);
+        }
+        // The second contest is left with player 2 alone, so it is dropped.
+        assert!(system.get_rating_of(&2).is_none());
+        assert_eq!(system.contests_applied(), 2);
+        assert_eq!(
+            system.remove_player(10),
+            Err(EloError::IncompleteHistory(10))
+        );
+    }
+}
+
+#[test]
+fn snapshot_test_1() {
+    let mut system = EloMmr::default()
+        .with_checkpoint_limit(2)
+        .with_contest_log()
+        .with_performance_model(PerformanceModel::Gaussian);
+    for time in 0..3 {
+        system
+            .update(vec![(1, 300), (2, 200), (3, 100 * time)], time)
+            .unwrap();
+    }
+
+    let header = system.snapshot_header();
+    assert_eq!(header.version, SNAPSHOT_VERSION);
+    assert_eq!(header.contests_applied, 3);
+    assert_eq!(header.hyperparameters.model, PerformanceModel::Gaussian);
+
+    let from_json = EloMmr::from_json(&system.to_json().unwrap()).unwrap();
+    let from_binary = EloMmr::from_binary(&system.to_binary().unwrap()).unwrap();
+    for mut loaded in [from_json, from_binary] {
+        assert_eq!(loaded.snapshot_header(), header);
+        for id in 1..=3 {
+            assert_eq!(loaded.get_player(&id), system.get_player(&id));
+        }
+        assert_eq!(loaded.num_checkpoints(), 2);
+        assert!(loaded.rollback().is_some());
+        assert_eq!(loaded.contests_applied(), 2);
+    }
+
+    let newer = system.to_json().unwrap().replacen(
+        &format!(r#""version":{}"#, SNAPSHOT_VERSION),
+        r#""version":99"#,
+        1,
+    );
+    assert!(matches!(
+        EloMmr::<u64>::from_json(&newer),
+        Err(SnapshotError::UnsupportedVersion(99))
+    ));
+    assert!(matches!(
+        EloMmr::<u64>::from_binary(b"not a snapshot"),
+        Err(SnapshotError::NotASnapshot)
+    ));
+
+    // Version 0 only held the superparameters and the players.
+    let legacy = r#"{
+        "rho": 1.0, "beta": 200.0, "gamma": 80.0, "mu_init": 1500.0, "sigma_init": 350.0,
+        "players": {
+            "1": {
+                "mu": 1600.0, "mu_pi": 0.0, "sigma": 150.0, "delta": 0.0,
+                "perfs": [1500.0, 1700.0], "weights": [8e-6, 2.5e-5]
+            }
+        }
+    }"#;
+    let loaded = EloMmr::<u64>::from_json(legacy).unwrap();
+    let player = loaded.get_player(&1).unwrap();
+    assert_eq!((player.mu, player.sigma), (1600.0, 150.0));
+    assert_eq!(player.num_contests, 1);
+    assert_eq!(loaded.contests_applied(), 0);
+    assert!(matches!(
+        EloMmr::<u64>::from_json(&legacy.replace("200.0", "0.0")),
+        Err(SnapshotError::Invalid(EloError::InvalidParameter("beta")))
+    ));
+    assert!(matches!(
+        EloMmr::<u64>::from_json(&legacy.replace("[1500.0, 1700.0]", "[]")),
+        Err(SnapshotError::InvalidPlayer)
+    ));
+}
+
+#[test]
+fn generic_id_test_1() {
+    let mut numeric = EloMmr::default();
+    let mut named = EloMmr::default().with_contest_log();
+    numeric
+        .update(vec![(1u64, 300), (2, 200), (3, 100)], 0)
+        .unwrap();
+    named
+        .update(
+            vec![
+                ("a".to_string(), 300),
+                ("b".to_string(), 200),
+                ("c".to_string(), 100),
+            ],
+            0,
+        )
+        .unwrap();
+    numeric
+        .update_teams(vec![(vec![1, 2], 100), (vec![3], 200)], 1)
+        .unwrap();
+    named
+        .update_teams(
+            vec![
+                (vec!["a".to_string(), "b".to_string()], 100),
+                (vec!["c".to_string()], 200),
+            ],
+            1,
+        )
+        .unwrap();
+
+    for (id, name) in [(1, "a"), (2, "b"), (3, "c")] {
+        assert_eq!(numeric.get_player(&id), named.get_player(&name.to_string()));
+    }
+    assert_eq!(
+        named.update(vec![("a".to_string(), 1), ("a".to_string(), 2)], 2),
+        Err(EloError::DuplicatePlayer("a".to_string()))
+    );
+
+    let replayed = named.remove_player("c".to_string()).unwrap();
+    assert_eq!(replayed.len(), 2);
+    assert!(named.get_rating_of(&"c".to_string()).is_none());
+    let loaded = EloMmr::<String>::from_binary(&named.to_binary().unwrap()).unwrap();
+    assert_eq!(loaded.get_ratings().len(), 2);
+}
+
+#[test]
+fn simulation_test_1() {
+    let simulation = Simulation::default().with_seed(42);
+    let report = simulation.run(&mut EloMmr::default()).unwrap();
+    assert_eq!(report, simulation.run(&mut EloMmr::default()).unwrap());
+    assert_eq!(report.rank_correlation.len(), 100);
+
+    let correlation = *report.rank_correlation.last().unwrap();
+    assert!(correlation > 0.9, "correlation {correlation}");
+    let converged = report.contests_to_converge(0.95).unwrap();
+    assert!(converged <= 60, "converged after {converged} contests");
+    let error = *report.rating_error.last().unwrap();
+    assert!(error < report.rating_error[0]);
+    assert!(error < 150.0, "rating error {error}");
+
+    let noisy = simulation
+        .with_noise(600.0)
+        .run(&mut EloMmr::default())
+        .unwrap();
+    assert!(*noisy.rank_correlation.last().unwrap() < correlation);
+}
+
+#[test]
+fn simulation_test_2() {
+    assert_eq!(spearman(&[1.0, 2.0, 3.0], &[10.0, 20.0, 30.0]), 1.0);
+    assert_eq!(spearman(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]), -1.0);
+    assert_eq!(spearman(&[1.0, 1.0], &[1.0, 2.0]), 0.0);
+    assert!(
+        (spearman(&[1.0, 2.0, 2.0, 3.0], &[1.0, 2.0, 3.0, 4.0]) - 0.9486832980505138).abs() < 1e-12
+    );
+
+    let report = Simulation::new(50, 60, 10)
+        .with_seed(7)
+        .run(&mut TopCoder::default())
+        .unwrap();
+    let correlation = *report.rank_correlation.last().unwrap();
+    assert!(correlation > 0.8, "correlation {correlation}");
 }
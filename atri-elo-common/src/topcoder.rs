@@ -0,0 +1,224 @@
+use dashmap::DashMap;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::{standings, EloError, RatingSystem};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Player {
+    rating: f64,
+    volatility: f64,
+    num_contests: usize,
+}
+
+/// An implementation of TopCoder SRM rating algorithm.
+///
+/// Tied players are all given the average rank of their tie.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopCoder {
+    rating_init: f64,
+    volatility_init: f64,
+
+    players: DashMap<u64, Player>,
+}
+
+impl Default for TopCoder {
+    fn default() -> Self {
+        Self::new(1200.0, 535.0)
+    }
+}
+
+impl TopCoder {
+    /// Construct a new system.
+    ///
+    /// Default::default() gives a preset of superparameters (r_init = 1200, vol_init = 535).
+    pub fn new(rating_init: f64, volatility_init: f64) -> TopCoder {
+        TopCoder {
+            rating_init,
+            volatility_init,
+            players: DashMap::new(),
+        }
+    }
+
+    /// Get the volatility of the specified player.
+    pub fn get_volatility_of(&self, id: &u64) -> Option<f64> {
+        self.players.get(id).map(|player| player.volatility)
+    }
+}
+
+impl RatingSystem for TopCoder {
+    type Error = EloError;
+
+    /// Update ratings according to the explicit standings of the provided contest.
+    ///
+    /// The contest time is ignored.
+    ///
+    /// The returned performance is `PerfAs`, the rating the participant performed as in this contest.
+    fn update_standings(
+        &mut self,
+        mut standings: Vec<(u64, u64, u64)>,
+        _time: u64,
+    ) -> Result<Vec<(u64, f64, f64)>, EloError> {
+        if standings.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        standings::check(&mut standings)?;
+        let players: Vec<Player> = standings
+            .iter()
+            .map(|(id, _, _)| {
+                self.players
+                    .get(id)
+                    .map(|player| player.clone())
+                    .unwrap_or(Player {
+                        rating: self.rating_init,
+                        volatility: self.volatility_init,
+                        num_contests: 0,
+                    })
+            })
+            .collect();
+
+        let n = players.len() as f64;
+        let ave_rating = players.iter().map(|player| player.rating).sum::<f64>() / n;
+        let competition_factor = if players.len() > 1 {
+            let vol_term = players
+                .iter()
+                .map(|player| player.volatility.powi(2))
+                .sum::<f64>()
+                / n;
+            let rating_term = players
+                .iter()
+                .map(|player| (player.rating - ave_rating).powi(2))
+                .sum::<f64>()
+                / (n - 1.0);
+            (vol_term + rating_term).sqrt()
+        } else {
+            0.0
+        };
+
+        let mut result = Vec::with_capacity(standings.len());
+        standings
+            .par_iter()
+            .zip(players.par_iter())
+            .map(|(&(id, lo, hi), player)| {
+                let expected_rank = 0.5
+                    + players
+                        .iter()
+                        .map(|other| {
+                            let z = (other.rating - player.rating)
+                                / (2.0 * (player.volatility.powi(2) + other.volatility.powi(2)))
+                                    .sqrt();
+                            0.5 * (erf(z) + 1.0)
+                        })
+                        .sum::<f64>();
+                let actual_rank = 0.5 * (lo + hi) as f64;
+
+                let expected_perf = -inverse_normal_cdf((expected_rank - 0.5) / n);
+                let actual_perf = -inverse_normal_cdf((actual_rank - 0.5) / n);
+                let perf = player.rating + competition_factor * (actual_perf - expected_perf);
+
+                let mut weight =
+                    (1.0 - (0.42 / (player.num_contests + 1) as f64 + 0.18)).recip() - 1.0;
+                if player.rating >= 2500.0 {
+                    weight *= 0.8;
+                } else if player.rating >= 2000.0 {
+                    weight *= 0.9;
+                }
+                let cap = 150.0 + 1500.0 / (player.num_contests + 2) as f64;
+
+                let rating = ((player.rating + weight * perf) / (1.0 + weight))
+                    .clamp(player.rating - cap, player.rating + cap);
+                let volatility = ((rating - player.rating).powi(2) / weight
+                    + player.volatility.powi(2) / (weight + 1.0))
+                    .sqrt();
+
+                self.players.insert(
+                    id,
+                    Player {
+                        rating,
+                        volatility,
+                        num_contests: player.num_contests + 1,
+                    },
+                );
+                (id, perf, rating)
+            })
+            .collect_into_vec(&mut result);
+
+        Ok(result)
+    }
+
+    fn get_ratings(&self) -> Vec<(u64, f64)> {
+        self.players
+            .par_iter()
+            .map(|player| (*player.key(), player.rating))
+            .collect()
+    }
+
+    fn get_rating_of(&self, id: &u64) -> Option<f64> {
+        self.players.get(id).map(|player| player.rating)
+    }
+}
+
+/// The error function, with an absolute error below 1.5e-7 (Abramowitz and Stegun 7.1.26).
+pub(crate) fn erf(x: f64) -> f64 {
+    const P: f64 = 0.3275911;
+    const A: [f64; 5] = [
+        0.254829592,
+        -0.284496736,
+        1.421413741,
+        -1.453152027,
+        1.061405429,
+    ];
+
+    let t = (1.0 + P * x.abs()).recip();
+    let poly = A.iter().rev().fold(0.0, |acc, a| acc * t + a) * t;
+    (1.0 - poly * (-x * x).exp()).copysign(x)
+}
+
+/// The inverse of the standard normal CDF, with a relative error below 1.15e-9 (Acklam's algorithm).
+pub(crate) fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let poly = |coeffs: &[f64], x: f64| coeffs.iter().fold(0.0, |acc, c| acc * x + c);
+
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        poly(&C, q) / (poly(&D, q) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        poly(&A, r) * q / (poly(&B, r) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -poly(&C, q) / (poly(&D, q) * q + 1.0)
+    }
+}
@@ -1,13 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
-use color_eyre::eyre::{eyre, Result};
-use oauth2::{
-    basic::BasicClient, reqwest::async_http_client, AccessToken, AuthUrl, ClientId, ClientSecret,
-    RefreshToken, TokenResponse, TokenUrl,
-};
+use atri_elo_common::{ContestRecord, ScoreOrder};
+use color_eyre::eyre::Result;
+use oauth2::{AccessToken, RefreshToken};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use sled::Db;
 use time::OffsetDateTime;
 
@@ -59,7 +56,8 @@ impl User {
             expires_in,
             refresh_token,
             cookie_master_key,
-            rating: config::elo::MU_INIT(),
+            rating: config::elo::MU_INIT()
+                - config::elo::DISPLAY_SIGMA_FACTOR() * config::elo::SIGMA_INIT(),
             rank: 0,
             history: HashMap::new(),
             avatar_url,
@@ -67,7 +65,7 @@ impl User {
     }
 
     pub fn get(id: u64) -> Result<Option<User>> {
-        Ok(match DATABASE.open_tree("users")?.get(&id.to_be_bytes())? {
+        Ok(match DATABASE.open_tree("users")?.get(id.to_be_bytes())? {
             Some(buf) => Some(deserialize(&buf)?),
             None => None,
         })
@@ -78,66 +76,10 @@ impl User {
 
         DATABASE
             .open_tree("users")?
-            .insert(&self.id.to_be_bytes(), buf)?;
+            .insert(self.id.to_be_bytes(), buf)?;
 
         Ok(())
     }
-
-    pub async fn update_username(&mut self) -> Result<()> {
-        let res = reqwest::Client::new()
-            .get(config::OSU_USER_API_ENDPOINT())
-            .bearer_auth(self.access_token().await?.secret())
-            .send()
-            .await?
-            .json::<Value>()
-            .await?;
-
-        self.username = res
-            .get("username")
-            .ok_or_else(|| eyre!("username not presented in response"))?
-            .as_str()
-            .ok_or_else(|| eyre!("username not representable by &str"))?
-            .to_string();
-
-        self.save()?;
-
-        Ok(())
-    }
-
-    pub async fn access_token(&mut self) -> Result<&AccessToken> {
-        if OffsetDateTime::now_utc() >= self.expires_in {
-            let client = BasicClient::new(
-                ClientId::new(config::oauth::CLIENT_ID()),
-                Some(ClientSecret::new(config::oauth::CLIENT_SECRET())),
-                AuthUrl::new(config::oauth::AUTH_URL())?,
-                Some(TokenUrl::new(config::oauth::TOKEN_URL())?),
-            );
-
-            let token_result = client
-                .exchange_refresh_token(&self.refresh_token)
-                .request_async(async_http_client)
-                .await?;
-
-            if let Some(refresh_token) = token_result.refresh_token() {
-                self.refresh_token = refresh_token.clone();
-            }
-
-            match token_result.expires_in() {
-                Some(expires_in) => {
-                    self.expires_in = OffsetDateTime::now_utc()
-                        + expires_in / config::oauth::EXPIRE_TIME_FACTOR();
-                }
-                None => {
-                    return Err(eyre!("expires info not presented in response"));
-                }
-            }
-
-            self.access_token = token_result.access_token().clone();
-
-            self.save()?;
-        }
-        Ok(&self.access_token)
-    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -145,34 +87,27 @@ pub struct ContestGroup {
     pub id: u64,
     pub name: String,
     pub contests: HashSet<u64>,
+    /// Weight of every contest in the group, multiplied with the contest's own weight.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
 }
 
-impl ContestGroup {
-    pub fn new(id: u64, name: String, contests: HashSet<u64>) -> Self {
-        Self { id, name, contests }
-    }
+fn default_weight() -> f64 {
+    1.0
+}
 
+impl ContestGroup {
     pub fn get(id: u64) -> Result<Option<ContestGroup>> {
         Ok(
             match DATABASE
                 .open_tree("contest_groups")?
-                .get(&id.to_be_bytes())?
+                .get(id.to_be_bytes())?
             {
                 Some(buf) => Some(deserialize(&buf)?),
                 None => None,
             },
         )
     }
-
-    pub fn save(&self) -> Result<()> {
-        let buf = serialize(&self)?;
-
-        DATABASE
-            .open_tree("contest_groups")?
-            .insert(&self.id.to_be_bytes(), buf)?;
-
-        Ok(())
-    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -208,47 +143,101 @@ pub struct Contest {
     pub rank_time: Option<OffsetDateTime>,
     pub scores: HashMap<u64, u64>,
     pub detail: HashMap<u64, ContestDetail>,
+    /// Teams of a team contest, or empty for an individual contest.
+    #[serde(default)]
+    pub teams: Vec<Vec<u64>>,
+    /// Weight of the contest, below 1 for low-stakes events.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
 }
 
 impl Contest {
+    /// Build the record to apply to the rating system.
+    ///
+    /// In a team contest, a team's score is the sum of its members' scores.
+    /// The contest counts with its own weight times the weight of its group, and is tagged with its id.
+    pub fn record(&self) -> Result<ContestRecord> {
+        let time = self.close_time.unix_timestamp().max(0) as u64;
+
+        let record = if self.teams.is_empty() {
+            let scores = self
+                .scores
+                .iter()
+                .map(|(&uid, &score)| (uid, score))
+                .collect();
+            ContestRecord::from_scores(scores, ScoreOrder::HigherIsBetter, time)?
+        } else {
+            let team_scores = self
+                .teams
+                .iter()
+                .map(|team| {
+                    let score = team
+                        .iter()
+                        .filter_map(|uid| self.scores.get(uid))
+                        .sum::<u64>();
+                    (team.clone(), score)
+                })
+                .collect();
+            ContestRecord::from_team_scores(team_scores, ScoreOrder::HigherIsBetter, time)?
+        };
+
+        let group_weight = ContestGroup::get(self.group_id)?.map_or(1.0, |group| group.weight);
+
+        Ok(record
+            .with_weight(self.weight * group_weight)
+            .with_id(self.id))
+    }
+
+    pub fn get(id: u64) -> Result<Option<Contest>> {
+        Ok(
+            match DATABASE.open_tree("contests")?.get(id.to_be_bytes())? {
+                Some(buf) => Some(deserialize(&buf)?),
+                None => None,
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Season {
+    pub id: u64,
+    pub name: String,
+    pub end_time: OffsetDateTime,
+    /// Final `(rating, rank)` of every player, with rank 0 for provisional players.
+    pub leaderboard: HashMap<u64, (f64, u64)>,
+}
+
+impl Season {
     pub fn new(
         id: u64,
         name: String,
-        group_id: u64,
-        beatmap_id: u64,
-        open_time: OffsetDateTime,
-        close_time: OffsetDateTime,
+        end_time: OffsetDateTime,
+        leaderboard: HashMap<u64, (f64, u64)>,
     ) -> Self {
         Self {
             id,
             name,
-            group_id,
-            beatmap_id,
-            status: 0,
-            open_time,
-            close_time,
-            rank_time: None,
-            scores: HashMap::new(),
-            detail: HashMap::new(),
+            end_time,
+            leaderboard,
         }
     }
 
-    pub fn get(id: u64) -> Result<Option<Contest>> {
+    pub fn get(id: u64) -> Result<Option<Season>> {
         Ok(
-            match DATABASE.open_tree("contests")?.get(&id.to_be_bytes())? {
+            match DATABASE.open_tree("seasons")?.get(id.to_be_bytes())? {
                 Some(buf) => Some(deserialize(&buf)?),
                 None => None,
             },
         )
     }
 
-    pub fn save(&self) -> Result<()> {
-        let buf = serialize(&self)?;
-
+    /// Get all seasons in order of id.
+    pub fn all() -> Result<Vec<Season>> {
         DATABASE
-            .open_tree("contests")?
-            .insert(&self.id.to_be_bytes(), buf)?;
-
-        Ok(())
+            .open_tree("seasons")?
+            .iter()
+            .values()
+            .map(|buf| deserialize(&buf?))
+            .collect()
     }
 }
@@ -9,6 +9,8 @@ mod general;
 
 mod util;
 
+mod ranking;
+
 mod pages;
 
 config! {
@@ -22,6 +24,11 @@ config! {
         GAMMA:f64 => 80.0,
         MU_INIT:f64 => 1500.0,
         SIGMA_INIT:f64 => 350.0,
+        DISPLAY_SIGMA_FACTOR:f64 => 2.0,
+        PROVISIONAL_SIGMA:f64 => 150.0,
+        PROVISIONAL_CONTESTS:usize => 3,
+        SEASON_RESET_FRACTION:f64 => 0.5,
+        SEASON_SIGMA_INCREASE:f64 => 100.0,
     },
 
     oauth {
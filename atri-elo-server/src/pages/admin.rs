@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, Query},
+    http::HeaderMap,
+};
+use color_eyre::eyre::Result;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tokio::task;
+
+use crate::{
+    config,
+    ranking::{self, Outcome},
+};
+
+use super::handle_error;
+
+/// The header carrying the admin key, kept out of the URL so that it does not end up in access logs.
+const KEY_HEADER: &str = "x-admin-key";
+
+/// Check the admin key of the request, comparing it in constant time.
+fn check_key(headers: &HeaderMap) -> Result<(), StatusCode> {
+    let key = headers
+        .get(KEY_HEADER)
+        .ok_or(StatusCode::FORBIDDEN)?
+        .as_bytes();
+    let expected = config::admin::KEY();
+    let expected = expected.as_bytes();
+
+    let diff = key
+        .iter()
+        .zip(expected)
+        .fold(0, |diff, (a, b)| diff | (a ^ b));
+    if key.len() != expected.len() || diff != 0 {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(())
+}
+
+/// Run an operation on the rating system, which blocks on the database, off the async runtime.
+async fn run<T: Send + 'static>(
+    operation: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T, StatusCode> {
+    task::spawn_blocking(operation)
+        .await
+        .map_err(handle_error)?
+        .map_err(handle_error)
+}
+
+fn status(outcome: Outcome) -> Result<StatusCode, StatusCode> {
+    match outcome {
+        Outcome::Done => Ok(StatusCode::NO_CONTENT),
+        Outcome::NotFound => Err(StatusCode::NOT_FOUND),
+        Outcome::AlreadyDone => Err(StatusCode::CONFLICT),
+    }
+}
+
+/// Remove the user from every contest and recompute the ratings, e.g. after they were found cheating.
+pub async fn remove_user(
+    Path(user_id): Path<u64>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    check_key(&headers)?;
+
+    run(move || ranking::remove_user(user_id)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeasonQuery {
+    name: String,
+}
+
+/// End the current season under the given id, archiving its leaderboard and softly resetting every player.
+pub async fn end_season(
+    Path(season_id): Path<u64>,
+    Query(query): Query<SeasonQuery>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    check_key(&headers)?;
+
+    status(run(move || ranking::end_season(season_id, query.name)).await?)
+}
+
+/// Merge the user `from` into the user `into`, e.g. when one person has played on two accounts.
+pub async fn merge_users(
+    Path((into, from)): Path<(u64, u64)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    check_key(&headers)?;
+
+    run(move || ranking::merge_users(into, from)).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Rate the contest, recording its results and updating every user's rating and rank.
+pub async fn rate_contest(
+    Path(contest_id): Path<u64>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    check_key(&headers)?;
+
+    status(run(move || ranking::rate_contest(contest_id)).await?)
+}
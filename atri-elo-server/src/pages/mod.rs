@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use axum::{http::StatusCode, routing::get, Router};
+use axum::{http::StatusCode, routing::{get, post}, Router};
 use color_eyre::Report;
 
 use maud::{html, Markup};
@@ -10,11 +10,14 @@ use tracing::error;
 use crate::config;
 
 use self::{
+    admin::{end_season, merge_users, rate_contest, remove_user},
     oauth::{oauth_callback, oauth_logout, oauth_verify},
     root::root,
     user::{user, user_with_id}
 };
 
+mod admin;
+
 mod oauth;
 
 mod root;
@@ -30,6 +33,10 @@ pub fn router() -> Router {
         .route("/oauth/logout", get(oauth_logout))
         .route("/user", get(user))
         .route("/user/:user_id", get(user_with_id))
+        .route("/admin/rate/:contest_id", post(rate_contest))
+        .route("/admin/remove/:user_id", post(remove_user))
+        .route("/admin/season/:season_id", post(end_season))
+        .route("/admin/merge/:into/:from", post(merge_users))
 }
 
 fn handle_error(err: impl Into<Report> + Display) -> StatusCode {
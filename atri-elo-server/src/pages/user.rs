@@ -1,12 +1,55 @@
 use axum::{response::Html, extract::Path};
-use maud::{html, DOCTYPE};
+use color_eyre::eyre::Result;
+use maud::{html, Markup, DOCTYPE};
 use reqwest::StatusCode;
 use tower_cookies::Cookies;
 
-use crate::{pages::header, general::User};
+use crate::{pages::header, general::{Season, User}};
 
 use super::{handle_error, oauth::get_user_by_cookie};
 
+fn season_table(user_id: u64) -> Result<Markup> {
+    let results: Vec<_> = Season::all()?
+        .into_iter()
+        .filter_map(|season| {
+            let (rating, rank) = *season.leaderboard.get(&user_id)?;
+            Some((season.name, rating, rank))
+        })
+        .collect();
+
+    Ok(html! {
+        @if !results.is_empty() {
+            .box {
+                p .title."is-4" { "Seasons" }
+                table .table.is-fullwidth {
+                    thead {
+                        tr {
+                            th { "Season" }
+                            th { "Rating" }
+                            th { "Rank" }
+                        }
+                    }
+                    tbody {
+                        @for (name, rating, rank) in results {
+                            tr {
+                                td { (name) }
+                                td { (format!("{:.0}", rating)) }
+                                td {
+                                    @if rank == 0 {
+                                        "Provisional"
+                                    } @else {
+                                        "#" (rank)
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
 fn empty_user_page() -> Html<String> {
     Html(
         html! {
@@ -41,6 +84,7 @@ pub async fn user(cookies: Cookies) -> Result<Html<String>, StatusCode> {
     }
 
     let user = user.unwrap();
+    let seasons = season_table(user.id).map_err(handle_error)?;
 
     Ok(Html(
         html! {
@@ -71,6 +115,7 @@ pub async fn user(cookies: Cookies) -> Result<Html<String>, StatusCode> {
                             }
                         }
                     }
+                    (seasons)
                 }
             }
         }
@@ -86,6 +131,7 @@ pub async fn user_with_id(Path(user_id): Path<u64>) -> Result<Html<String>, Stat
     }
 
     let user = user.unwrap();
+    let seasons = season_table(user.id).map_err(handle_error)?;
 
     Ok(Html(
         html! {
@@ -116,6 +162,7 @@ pub async fn user_with_id(Path(user_id): Path<u64>) -> Result<Html<String>, Stat
                             }
                         }
                     }
+                    (seasons)
                 }
             }
         }
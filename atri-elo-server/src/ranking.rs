@@ -0,0 +1,352 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::Infallible,
+    sync::{Mutex, MutexGuard},
+};
+
+use atri_elo_common::{ContestRecord, EloMmr, Replayed};
+use color_eyre::eyre::Result;
+use sled::{transaction::ConflictableTransactionError, Transactional};
+use time::OffsetDateTime;
+
+use crate::{
+    config,
+    general::{Contest, ContestDetail, PlayerHistory, Season, User, DATABASE},
+    util::serialize,
+};
+
+const SYSTEM_KEY: &str = "elo_mmr";
+
+/// Held by every operation that loads, changes and saves the system, so that they do not overwrite each other.
+static SYSTEM_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_system() -> MutexGuard<'static, ()> {
+    // The lock guards no data, so a panic while holding it leaves nothing to recover.
+    SYSTEM_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The outcome of rating a contest or ending a season.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Done,
+    /// The contest does not exist.
+    NotFound,
+    /// The contest was already rated, or the season already ended.
+    AlreadyDone,
+}
+
+/// Load the rating system from the database, or construct a new one from the config.
+///
+/// Display settings always follow the config. Contests are logged so that users can be removed later.
+pub fn load_system() -> Result<EloMmr> {
+    let system = match DATABASE.open_tree("system")?.get(SYSTEM_KEY)? {
+        Some(buf) => EloMmr::from_binary(&buf)?,
+        None => EloMmr::try_new(
+            config::elo::RHO(),
+            config::elo::BETA(),
+            config::elo::GAMMA(),
+            config::elo::MU_INIT(),
+            config::elo::SIGMA_INIT(),
+        )?,
+    };
+
+    Ok(system
+        .with_contest_log()
+        .with_display_rating(config::elo::DISPLAY_SIGMA_FACTOR())
+        .with_provisional(
+            config::elo::PROVISIONAL_SIGMA(),
+            config::elo::PROVISIONAL_CONTESTS(),
+        ))
+}
+
+/// The records changed by an operation on the system, committed together with it.
+#[derive(Default)]
+struct Changes {
+    users: HashMap<u64, User>,
+    contests: Vec<Contest>,
+    season: Option<Season>,
+}
+
+impl Changes {
+    /// Get the user to change, loading it unless it was changed already.
+    fn user(&mut self, id: u64) -> Result<Option<&mut User>> {
+        Ok(Some(match self.users.entry(id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match User::get(id)? {
+                Some(user) => entry.insert(user),
+                None => return Ok(None),
+            },
+        }))
+    }
+}
+
+/// Save the system and the records changed with it in a single transaction, so that they are written all or none.
+fn commit(system: &EloMmr, changes: Changes) -> Result<()> {
+    let system_buf = system.to_binary()?;
+    let users = changes
+        .users
+        .values()
+        .map(|user| Ok((user.id, serialize(user)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let contests = changes
+        .contests
+        .iter()
+        .map(|contest| Ok((contest.id, serialize(contest)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let season = match changes.season {
+        Some(ref season) => Some((season.id, serialize(season)?)),
+        None => None,
+    };
+
+    let system_tree = DATABASE.open_tree("system")?;
+    let user_tree = DATABASE.open_tree("users")?;
+    let contest_tree = DATABASE.open_tree("contests")?;
+    let season_tree = DATABASE.open_tree("seasons")?;
+    (&system_tree, &user_tree, &contest_tree, &season_tree).transaction(
+        |(system_tree, user_tree, contest_tree, season_tree)| {
+            system_tree.insert(SYSTEM_KEY, system_buf.as_slice())?;
+            for (id, buf) in &users {
+                user_tree.insert(&id.to_be_bytes(), buf.as_slice())?;
+            }
+            for (id, buf) in &contests {
+                contest_tree.insert(&id.to_be_bytes(), buf.as_slice())?;
+            }
+            if let Some((id, ref buf)) = season {
+                season_tree.insert(&id.to_be_bytes(), buf.as_slice())?;
+            }
+            Ok::<_, ConflictableTransactionError<Infallible>>(())
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Rank all players by display rating, returning `(rating, rank)` of each player.
+///
+/// Provisional players are left unranked with rank 0.
+pub fn leaderboard(system: &EloMmr) -> HashMap<u64, (f64, u64)> {
+    let mut ratings = system.get_display_ratings();
+    ratings.sort_unstable_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut rank = 0;
+    ratings
+        .into_iter()
+        .map(|(id, rating)| {
+            if system.is_provisional(&id) == Some(true) {
+                (id, (rating, 0))
+            } else {
+                rank += 1;
+                (id, (rating, rank))
+            }
+        })
+        .collect()
+}
+
+/// Update every user's rating and rank from the leaderboard, returning the leaderboard.
+fn update_users(system: &EloMmr, changes: &mut Changes) -> Result<HashMap<u64, (f64, u64)>> {
+    let leaderboard = leaderboard(system);
+
+    for (&id, &(rating, rank)) in &leaderboard {
+        if let Some(user) = changes.user(id)? {
+            user.rating = rating;
+            user.rank = rank;
+        }
+    }
+
+    Ok(leaderboard)
+}
+
+/// The rank of every user in the contest.
+fn contest_ranks(record: &ContestRecord) -> HashMap<u64, u64> {
+    let mut contest_ranks = HashMap::new();
+    match record.teams {
+        Some(ref teams) => {
+            for &(index, lo, _) in &record.team_standings {
+                contest_ranks.extend(teams[index as usize].iter().map(|&uid| (uid, lo)));
+            }
+        }
+        None => contest_ranks.extend(record.standings.iter().map(|&(uid, lo, _)| (uid, lo))),
+    }
+    contest_ranks
+}
+
+/// Record the result of a user in the contest and in their history.
+fn record_result(
+    contest: &mut Contest,
+    uid: u64,
+    perf: f64,
+    rating: f64,
+    contest_rank: u64,
+    rating_rank: u64,
+    changes: &mut Changes,
+) -> Result<()> {
+    contest.detail.insert(
+        uid,
+        ContestDetail::new(uid, perf, rating, contest_rank as f64, rating_rank as f64),
+    );
+
+    if let Some(user) = changes.user(uid)? {
+        user.history.insert(
+            contest.id,
+            PlayerHistory {
+                contest_id: contest.id,
+                perf,
+                rating,
+                contest_rank,
+                rating_rank,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+/// Apply the contest to the rating system, recording the results in the contest and its participants' histories.
+pub fn rate_contest(contest_id: u64) -> Result<Outcome> {
+    let _lock = lock_system();
+    let mut contest = match Contest::get(contest_id)? {
+        Some(contest) => contest,
+        None => return Ok(Outcome::NotFound),
+    };
+    if contest.rank_time.is_some() {
+        return Ok(Outcome::AlreadyDone);
+    }
+
+    let mut system = load_system()?;
+
+    let record = contest.record()?;
+    let contest_ranks = contest_ranks(&record);
+
+    let result = system.apply(record)?;
+
+    let mut changes = Changes::default();
+    let leaderboard = update_users(&system, &mut changes)?;
+
+    for (uid, perf, _) in result {
+        let (rating, rating_rank) = leaderboard[&uid];
+        record_result(
+            &mut contest,
+            uid,
+            perf,
+            rating,
+            contest_ranks[&uid],
+            rating_rank,
+            &mut changes,
+        )?;
+    }
+
+    contest.rank_time = Some(OffsetDateTime::now_utc());
+    changes.contests.push(contest);
+    commit(&system, changes)?;
+
+    Ok(Outcome::Done)
+}
+
+/// End the current season, storing its final leaderboard and softly resetting every player.
+pub fn end_season(id: u64, name: String) -> Result<Outcome> {
+    let _lock = lock_system();
+    if Season::get(id)?.is_some() {
+        return Ok(Outcome::AlreadyDone);
+    }
+
+    let mut system = load_system()?;
+    let end_time = OffsetDateTime::now_utc();
+
+    let mut changes = Changes {
+        season: Some(Season::new(id, name, end_time, leaderboard(&system))),
+        ..Changes::default()
+    };
+
+    system.soft_reset(
+        end_time.unix_timestamp().max(0) as u64,
+        config::elo::SEASON_RESET_FRACTION(),
+        config::elo::SEASON_SIGMA_INCREASE(),
+    )?;
+    update_users(&system, &mut changes)?;
+    commit(&system, changes)?;
+
+    Ok(Outcome::Done)
+}
+
+/// Merge the user `from` into the user `into`, combining their ratings and contest histories.
+///
+/// Contests both users took part in keep the entry of `into`. `from` is left unranked with an empty history.
+pub fn merge_users(into: u64, from: u64) -> Result<()> {
+    let _lock = lock_system();
+    let mut system = load_system()?;
+    system.merge_players(into, from)?;
+
+    let mut changes = Changes::default();
+    if let Some(from_user) = changes.user(from)? {
+        let history = std::mem::take(&mut from_user.history);
+        from_user.rank = 0;
+        if let Some(into_user) = changes.user(into)? {
+            for (contest_id, history) in history {
+                into_user.history.entry(contest_id).or_insert(history);
+            }
+        }
+    }
+
+    update_users(&system, &mut changes)?;
+    commit(&system, changes)?;
+
+    Ok(())
+}
+
+/// Remove the user from every contest, e.g. a cheater, and rewrite the results of the contests that were replayed.
+///
+/// Users rated before the contest log was enabled cannot be removed, since their earlier contests were not
+/// logged. Rating ranks of replayed contests are taken from the current leaderboard.
+pub fn remove_user(uid: u64) -> Result<()> {
+    let _lock = lock_system();
+    let mut system = load_system()?;
+    let replayed = system.remove_player(uid)?;
+
+    let mut changes = Changes::default();
+    let leaderboard = update_users(&system, &mut changes)?;
+
+    for Replayed {
+        contest: record,
+        result,
+        display_ratings,
+    } in replayed
+    {
+        let mut contest = match record.id.map(Contest::get).transpose()?.flatten() {
+            Some(contest) => contest,
+            None => continue,
+        };
+
+        contest.scores.remove(&uid);
+        for team in contest.teams.iter_mut() {
+            team.retain(|&member| member != uid);
+        }
+        contest.teams.retain(|team| !team.is_empty());
+        contest.detail.clear();
+
+        let contest_ranks = contest_ranks(&record);
+        for ((id, perf, _), rating) in result.into_iter().zip(display_ratings) {
+            let rating_rank = leaderboard.get(&id).map_or(0, |&(_, rank)| rank);
+            record_result(
+                &mut contest,
+                id,
+                perf,
+                rating,
+                contest_ranks[&id],
+                rating_rank,
+                &mut changes,
+            )?;
+        }
+
+        changes.contests.push(contest);
+    }
+
+    if let Some(user) = changes.user(uid)? {
+        user.history.clear();
+        user.rank = 0;
+    }
+    commit(&system, changes)?;
+
+    Ok(())
+}